@@ -1,3 +1,5 @@
+#![allow(clippy::needless_borrows_for_generic_args)]
+
 use passes::barcode::{Barcode, BarcodeFormat};
 use passes::fields;
 use passes::resource;
@@ -31,6 +33,7 @@ fn main() {
         label_color: visual_appearance::Color::white(),
         foreground_color: visual_appearance::Color::white(),
         background_color: visual_appearance::Color::new(0, 143, 212),
+        footer_background_color: None,
     })
     .fields(
         fields::Type::BoardingPass {
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_borrows_for_generic_args)]
+
 use passes::{resource, sign, Package, PassBuilder, PassConfig};
 
 use std::fs::File;
@@ -52,8 +54,7 @@ fn main() {
     std::io::Read::read_to_end(&mut file_sign_key_cert, &mut sign_cert_key_data).unwrap();
 
     let sign_config =
-        sign::SignConfig::new(sign::WWDR::G4, &sign_cert_data, &sign_cert_key_data)
-            .unwrap();
+        sign::SignConfig::new(sign::WWDR::G4, &sign_cert_data, &sign_cert_key_data).unwrap();
     package.add_certificates(sign_config);
 
     // Save package as .pkpass
@@ -0,0 +1,509 @@
+//! Command-line tool for Apple Wallet passes: inspect, build, sign, validate and extract `.pkpass` files.
+//!
+//! Requires `cli` feature: `cargo install passes --features cli`.
+use std::{
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use passes::{
+    sign::{SignConfig, WWDR},
+    Error, Package,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "passes",
+    version,
+    about = "Build, inspect and sign Apple Wallet passes"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show pass.json, resources, manifest and signature status of .pkpass file
+    Inspect {
+        /// Package file (.pkpass)
+        file: PathBuf,
+
+        /// WWDR certificate (PEM) for signature check, Apple WWDR G4 by default
+        #[arg(long)]
+        wwdr: Option<PathBuf>,
+    },
+
+    /// Build .pkpass file from directory with pass.json, images and .lproj folders
+    Build {
+        /// Directory with pass.json
+        dir: PathBuf,
+
+        /// Output package file (.pkpass)
+        #[arg(short, long)]
+        output: PathBuf,
+
+        #[command(flatten)]
+        sign: SignArgs,
+    },
+
+    /// Sign .pkpass file
+    #[command(group(ArgGroup::new("signer").args(["cert", "p12"]).required(true)))]
+    Sign {
+        /// Package file (.pkpass)
+        file: PathBuf,
+
+        /// Output package file (.pkpass)
+        #[arg(short, long)]
+        output: PathBuf,
+
+        #[command(flatten)]
+        sign: SignArgs,
+    },
+
+    /// Check pass against Apple's rules, exits with error if Wallet rejects the pass
+    Validate {
+        /// Package file (.pkpass) or directory with pass.json
+        path: PathBuf,
+    },
+
+    /// Extract pass.json, images and .lproj folders of .pkpass file to directory
+    Extract {
+        /// Package file (.pkpass)
+        file: PathBuf,
+
+        /// Output directory
+        dir: PathBuf,
+    },
+}
+
+/// Signing certificates
+#[derive(Args)]
+struct SignArgs {
+    /// Signer certificate (PEM or DER)
+    #[arg(long, requires = "key", conflicts_with = "p12")]
+    cert: Option<PathBuf>,
+
+    /// Signer private key (PEM or DER)
+    #[arg(long, requires = "cert")]
+    key: Option<PathBuf>,
+
+    /// Signer certificate and key in PKCS#12 bundle (.p12)
+    #[arg(long)]
+    p12: Option<PathBuf>,
+
+    /// File with password of private key or PKCS#12 bundle, PASSES_PASSWORD environment variable by default
+    #[arg(long)]
+    password_file: Option<PathBuf>,
+
+    /// WWDR certificate (PEM), Apple WWDR G4 by default
+    #[arg(long)]
+    wwdr: Option<PathBuf>,
+}
+
+impl SignArgs {
+    /// Load sign config, `None` if certificates are not provided
+    fn load(&self) -> Result<Option<SignConfig>, Error> {
+        let wwdr_data = self.wwdr.as_deref().map(read_file).transpose()?;
+        let wwdr = wwdr(&wwdr_data);
+
+        if let Some(p12) = &self.p12 {
+            let password = self.password()?.unwrap_or_default();
+            return SignConfig::from_pkcs12(&read_file(p12)?, &password, wwdr).map(Some);
+        }
+        let (Some(cert), Some(key)) = (&self.cert, &self.key) else {
+            return Ok(None);
+        };
+        let (cert, key) = (read_file(cert)?, read_file(key)?);
+        match self.password()? {
+            Some(password) => SignConfig::with_password(wwdr, &cert, &key, password.as_bytes()),
+            None => SignConfig::new(wwdr, &cert, &key),
+        }
+        .map(Some)
+    }
+
+    /// Password from file (first line) or from environment variable, it's not passed in arguments
+    fn password(&self) -> Result<Option<String>, Error> {
+        match &self.password_file {
+            Some(path) => {
+                let data = read_file(path)?;
+                let password = String::from_utf8_lossy(&data);
+                Ok(Some(String::from(
+                    password.lines().next().unwrap_or_default(),
+                )))
+            }
+            None => Ok(std::env::var(PASSWORD_VAR).ok()),
+        }
+    }
+}
+
+/// Environment variable with password of private key or PKCS#12 bundle
+const PASSWORD_VAR: &str = "PASSES_PASSWORD";
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            let mut source = std::error::Error::source(&err);
+            while let Some(err) = source {
+                eprintln!("  caused by: {}", err);
+                source = err.source();
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<ExitCode, Error> {
+    match command {
+        Command::Inspect { file, wwdr } => {
+            let data = read_file(&file)?;
+            let package = Package::read(Cursor::new(&data))?;
+            inspect(&package, &data, wwdr.as_deref())?;
+        }
+        Command::Build { dir, output, sign } => {
+            let mut package = read_dir(&dir)?;
+            if let Some(config) = sign.load()? {
+                package.add_certificates(config);
+            }
+            write_package(&mut package, &output)?;
+        }
+        Command::Sign { file, output, sign } => {
+            let mut package = Package::read(Cursor::new(read_file(&file)?))?;
+            // Certificates are required by arguments parser
+            if let Some(config) = sign.load()? {
+                package.add_certificates(config);
+            }
+            write_package(&mut package, &output)?;
+        }
+        Command::Validate { path } => {
+            let package = if path.is_dir() {
+                read_dir(&path)?
+            } else {
+                Package::read(Cursor::new(read_file(&path)?))?
+            };
+            let diagnostics = package.validate();
+            for diagnostic in &diagnostics {
+                let severity = if diagnostic.is_error() {
+                    "error"
+                } else {
+                    "warning"
+                };
+                println!("{}: {}", severity, diagnostic);
+            }
+            if diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+                return Ok(ExitCode::FAILURE);
+            }
+            if diagnostics.is_empty() {
+                println!("pass is valid");
+            }
+        }
+        Command::Extract { file, dir } => {
+            let package = Package::read(Cursor::new(read_file(&file)?))?;
+            package.write_dir(&dir)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Print package contents
+fn inspect(package: &Package, data: &[u8], wwdr_path: Option<&Path>) -> Result<(), Error> {
+    let json = package.pass.make_json().map_err(|source| Error::Json {
+        file: String::from("pass.json"),
+        source,
+    })?;
+    println!("pass.json:\n{}\n", json);
+
+    println!("Resources:");
+    for resource in &package.resources {
+        println!(
+            "  {} ({} bytes)",
+            resource.filename(),
+            resource.as_bytes().len()
+        );
+    }
+    if package.personalization.is_some() {
+        println!("  personalization.json");
+    }
+    for (language, localization) in &package.localizations {
+        if !localization.strings.is_empty() {
+            println!(
+                "  {}.lproj/pass.strings ({} strings)",
+                language,
+                localization.strings.len()
+            );
+        }
+        for resource in &localization.resources {
+            println!(
+                "  {}.lproj/{} ({} bytes)",
+                language,
+                resource.filename(),
+                resource.as_bytes().len()
+            );
+        }
+    }
+
+    println!("\nManifest:");
+    match &package.manifest {
+        Some(manifest) => {
+            for item in manifest {
+                println!("  {}  {}", item.checksum, item.path);
+            }
+        }
+        None => println!("  missing"),
+    }
+
+    let wwdr_data = wwdr_path.map(read_file).transpose()?;
+    match Package::verify(Cursor::new(data), wwdr(&wwdr_data)) {
+        Ok(()) => println!("\nSignature: valid"),
+        Err(err) => println!("\nSignature: invalid ({})", err),
+    }
+    Ok(())
+}
+
+/// Read package from directory, warns about unknown files
+fn read_dir(dir: &Path) -> Result<Package, Error> {
+    let (package, unknown_files) = Package::from_dir(dir)?;
+    for path in unknown_files {
+        eprintln!("warning: skipping unknown file {}", path);
+    }
+    Ok(package)
+}
+
+fn write_package(package: &mut Package, path: &Path) -> Result<(), Error> {
+    let mut buf = Cursor::new(Vec::new());
+    package.write(&mut buf)?;
+    write_file(path, buf.get_ref())
+}
+
+/// WWDR certificate from file data, or Apple WWDR G4
+fn wwdr(data: &Option<Vec<u8>>) -> WWDR<'_> {
+    match data {
+        Some(data) => WWDR::Custom(data),
+        None => WWDR::G4,
+    }
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, Error> {
+    fs::read(path).map_err(|source| Error::Io {
+        file: Some(path.display().to_string()),
+        source,
+    })
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<(), Error> {
+    fs::write(path, data).map_err(|source| Error::Io {
+        file: Some(path.display().to_string()),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::symm::Cipher;
+    use passes::{resource, sign::TestSigner, PassBuilder, PassConfig};
+
+    use super::*;
+
+    /// Parse arguments and run command
+    fn run_args(args: &[&str]) -> Result<ExitCode, Error> {
+        let cli = Cli::try_parse_from([&["passes"], args].concat()).unwrap();
+        run(cli.command)
+    }
+
+    fn path(dir: &Path, name: &str) -> String {
+        dir.join(name).display().to_string()
+    }
+
+    /// Make directory with pass, returns temp directory
+    fn make_pass_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("passes-cli-{}-{}", name, std::process::id()));
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: String::from("Apple inc."),
+            description: String::from("Example pass"),
+            pass_type_identifier: String::from("com.example.pass"),
+            team_identifier: String::from("AA00AA0A0A"),
+            serial_number: String::from("ABCDEFG1234567890"),
+        })
+        .build();
+        let mut package = Package::new(pass);
+        package
+            .add_resource(
+                resource::Type::Icon(resource::Version::Standard),
+                &include_bytes!("../package/testdata/icon.png")[..],
+            )
+            .unwrap();
+        package.write_dir(dir.join("pass")).unwrap();
+        dir
+    }
+
+    /// Write WWDR, signer certificate and key (encrypted with password, if set) to directory
+    fn write_certificates(dir: &Path, password: Option<&[u8]>) {
+        let signer = TestSigner::new().unwrap();
+        let config = signer.config();
+        let key = match password {
+            Some(password) => config
+                .sign_key
+                .private_key_to_pem_pkcs8_passphrase(Cipher::aes_256_cbc(), password)
+                .unwrap(),
+            None => config.sign_key.private_key_to_pem_pkcs8().unwrap(),
+        };
+        fs::write(dir.join("wwdr.pem"), signer.wwdr_pem()).unwrap();
+        fs::write(dir.join("cert.pem"), config.sign_cert.to_pem().unwrap()).unwrap();
+        fs::write(dir.join("key.pem"), key).unwrap();
+    }
+
+    fn verify(dir: &Path, name: &str) -> Result<(), Error> {
+        let wwdr = fs::read(dir.join("wwdr.pem")).unwrap();
+        let data = fs::read(dir.join(name)).unwrap();
+        Package::verify(Cursor::new(data), WWDR::Custom(&wwdr))
+    }
+
+    #[test]
+    fn build_sign_validate_extract() {
+        let dir = make_pass_dir("build");
+        write_certificates(&dir, None);
+        let (pass, wwdr) = (path(&dir, "pass"), path(&dir, "wwdr.pem"));
+        let (cert, key) = (path(&dir, "cert.pem"), path(&dir, "key.pem"));
+
+        let signed = path(&dir, "signed.pkpass");
+        let code = run_args(&[
+            "build", &pass, "-o", &signed, "--cert", &cert, "--key", &key, "--wwdr", &wwdr,
+        ])
+        .unwrap();
+        assert_eq!(ExitCode::SUCCESS, code);
+        verify(&dir, "signed.pkpass").unwrap();
+
+        // Unsigned package is signed by sign command
+        let unsigned = path(&dir, "unsigned.pkpass");
+        run_args(&["build", &pass, "-o", &unsigned]).unwrap();
+        assert!(verify(&dir, "unsigned.pkpass").is_err());
+        let resigned = path(&dir, "resigned.pkpass");
+        let code = run_args(&[
+            "sign", &unsigned, "-o", &resigned, "--cert", &cert, "--key", &key, "--wwdr", &wwdr,
+        ])
+        .unwrap();
+        assert_eq!(ExitCode::SUCCESS, code);
+        verify(&dir, "resigned.pkpass").unwrap();
+
+        assert_eq!(ExitCode::SUCCESS, run_args(&["validate", &signed]).unwrap());
+        assert_eq!(ExitCode::SUCCESS, run_args(&["validate", &pass]).unwrap());
+
+        let extracted = dir.join("extracted");
+        let code = run_args(&["extract", &signed, &extracted.display().to_string()]).unwrap();
+        assert_eq!(ExitCode::SUCCESS, code);
+        assert!(extracted.join("pass.json").is_file());
+        assert!(extracted.join("icon.png").is_file());
+
+        // Pass without icon is rejected by Wallet
+        fs::remove_file(extracted.join("icon.png")).unwrap();
+        let code = run_args(&["validate", &extracted.display().to_string()]).unwrap();
+        assert_eq!(ExitCode::FAILURE, code);
+
+        let err = run_args(&["validate", &path(&dir, "missing.pkpass")])
+            .err()
+            .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(err, Error::Io { .. }));
+    }
+
+    #[test]
+    fn read_password_from_file_and_env() {
+        let dir = make_pass_dir("password");
+        write_certificates(&dir, Some(b"secret"));
+        fs::write(dir.join("password.txt"), b"secret\n").unwrap();
+        let (pass, wwdr) = (path(&dir, "pass"), path(&dir, "wwdr.pem"));
+        let (cert, key) = (path(&dir, "cert.pem"), path(&dir, "key.pem"));
+        let output = path(&dir, "pass.pkpass");
+        let build = |password: &[&str]| {
+            let args = [
+                &[
+                    "build", &pass, "-o", &output, "--cert", &cert, "--key", &key,
+                ][..],
+                &["--wwdr", &wwdr],
+                password,
+            ]
+            .concat();
+            run_args(&args)
+        };
+
+        let password_file = path(&dir, "password.txt");
+        build(&["--password-file", &password_file]).unwrap();
+        verify(&dir, "pass.pkpass").unwrap();
+
+        std::env::set_var(PASSWORD_VAR, "secret");
+        let result = build(&[]);
+        std::env::remove_var(PASSWORD_VAR);
+        result.unwrap();
+
+        let err = build(&[]).err();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(err, Some(Error::Sign { .. })));
+    }
+
+    #[test]
+    fn require_signing_certificates() {
+        use clap::error::ErrorKind;
+
+        let parse = |args: &[&str]| Cli::try_parse_from([&["passes"], args].concat());
+
+        let err = parse(&["sign", "pass.pkpass", "-o", "out.pkpass"])
+            .err()
+            .unwrap();
+        assert_eq!(ErrorKind::MissingRequiredArgument, err.kind());
+        let err = parse(&[
+            "sign",
+            "pass.pkpass",
+            "-o",
+            "out.pkpass",
+            "--cert",
+            "cert.pem",
+        ])
+        .err()
+        .unwrap();
+        assert_eq!(ErrorKind::MissingRequiredArgument, err.kind());
+        let err = parse(&[
+            "sign",
+            "pass.pkpass",
+            "-o",
+            "out.pkpass",
+            "--cert",
+            "cert.pem",
+            "--key",
+            "key.pem",
+            "--p12",
+            "cert.p12",
+        ])
+        .err()
+        .unwrap();
+        assert_eq!(ErrorKind::ArgumentConflict, err.kind());
+        let err = parse(&[
+            "sign",
+            "pass.pkpass",
+            "-o",
+            "out.pkpass",
+            "--password",
+            "secret",
+        ])
+        .err()
+        .unwrap();
+        assert_eq!(ErrorKind::UnknownArgument, err.kind());
+
+        assert!(parse(&[
+            "sign",
+            "pass.pkpass",
+            "-o",
+            "out.pkpass",
+            "--p12",
+            "cert.p12"
+        ])
+        .is_ok());
+        // Package is built unsigned without certificates
+        assert!(parse(&["build", "./pass", "-o", "out.pkpass"]).is_ok());
+    }
+}
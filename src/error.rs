@@ -0,0 +1,174 @@
+use std::fmt;
+
+/// Errors returned by package reading, writing, signing and pass parsing.
+///
+/// Every variant that wraps an underlying error keeps it as [source](std::error::Error::source),
+/// and names the file inside the package (if known) that caused the error.
+#[derive(Debug)]
+pub enum Error {
+    /// Error while reading or writing the zip archive (.pkpass)
+    Zip {
+        /// File in the archive, if error is related to a single file
+        file: Option<String>,
+        source: zip::result::ZipError,
+    },
+
+    /// Error while parsing or building a JSON file (pass.json, manifest.json, etc.)
+    Json {
+        /// JSON file name
+        file: String,
+        source: serde_json::Error,
+    },
+
+    /// Package doesn't contain pass.json
+    MissingPassJson,
+
+    /// File is listed in manifest.json, or required for verification, but missed in package
+    MissingFile {
+        /// Missed file name
+        file: String,
+    },
+
+    /// File in package is not listed in manifest.json
+    UnexpectedFile {
+        /// Unlisted file name
+        file: String,
+    },
+
+    /// SHA-1 checksum of file doesn't match checksum from manifest.json
+    ChecksumMismatch {
+        /// Modified file name
+        file: String,
+    },
+
+    /// Error while parsing a non-JSON file (pass.strings, etc.)
+    Parse {
+        /// File name
+        file: String,
+        /// Description of the error
+        message: String,
+    },
+
+    /// Error while loading certificates or signing package
+    Sign {
+        /// File (or certificate) that caused the error, if known
+        file: Option<String>,
+        source: openssl::error::ErrorStack,
+    },
+
+    /// Error of custom [signer](crate::sign::Signer), e.g. HSM (PKCS#11) or cloud KMS client
+    Signer {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// I/O error while reading or writing package data
+    Io {
+        /// File that caused the error, if known
+        file: Option<String>,
+        source: std::io::Error,
+    },
+
+    /// Pass or package content is invalid
+    Validation(String),
+
+    /// Error while rendering [barcode](crate::barcode::Barcode) image, e.g. message can't be encoded
+    Barcode {
+        /// Description of the error
+        message: String,
+    },
+
+    /// Variable of [template](crate::template::PassTemplate) is missed in data
+    MissingVariable {
+        /// Variable name
+        name: String,
+    },
+
+    /// Error of [registration store](crate::store::RegistrationStore) backend
+    Store {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// Error while sending push notification through [transport](crate::push::PushTransport)
+    Push {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Zip {
+                file: Some(file), ..
+            } => write!(f, "zip error in {}", file),
+            Error::Zip { file: None, .. } => write!(f, "zip error"),
+            Error::Json { file, .. } => write!(f, "invalid JSON in {}", file),
+            Error::MissingPassJson => write!(f, "pass.json is missed in package file"),
+            Error::MissingFile { file } => write!(f, "{} is missed in package file", file),
+            Error::UnexpectedFile { file } => write!(f, "{} is not listed in manifest", file),
+            Error::ChecksumMismatch { file } => write!(f, "checksum mismatch for {}", file),
+            Error::Parse { file, message } => write!(f, "invalid {}: {}", file, message),
+            Error::Sign {
+                file: Some(file), ..
+            } => write!(f, "signing error in {}", file),
+            Error::Sign { file: None, .. } => write!(f, "signing error"),
+            Error::Signer { .. } => write!(f, "signer error"),
+            Error::Io {
+                file: Some(file), ..
+            } => write!(f, "I/O error in {}", file),
+            Error::Io { file: None, .. } => write!(f, "I/O error"),
+            Error::Validation(message) => write!(f, "validation error: {}", message),
+            Error::Barcode { message } => write!(f, "barcode error: {}", message),
+            Error::MissingVariable { name } => write!(f, "template variable {} is missed", name),
+            Error::Store { .. } => write!(f, "registration store error"),
+            Error::Push { .. } => write!(f, "push notification error"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Zip { source, .. } => Some(source),
+            Error::Json { source, .. } => Some(source),
+            Error::Sign { source, .. } => Some(source),
+            Error::Signer { source } => Some(source.as_ref()),
+            Error::Io { source, .. } => Some(source),
+            Error::Store { source } => Some(source.as_ref()),
+            Error::Push { source } => Some(source.as_ref()),
+            Error::MissingPassJson
+            | Error::MissingFile { .. }
+            | Error::UnexpectedFile { .. }
+            | Error::ChecksumMismatch { .. }
+            | Error::Parse { .. }
+            | Error::Validation(_)
+            | Error::Barcode { .. }
+            | Error::MissingVariable { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+
+    use super::*;
+
+    #[test]
+    fn display_with_file() {
+        let err = Error::Io {
+            file: Some(String::from("icon.png")),
+            source: std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "eof"),
+        };
+
+        assert_eq!("I/O error in icon.png", err.to_string());
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn display_without_source() {
+        let err = Error::MissingPassJson;
+
+        assert_eq!("pass.json is missed in package file", err.to_string());
+        assert!(err.source().is_none());
+    }
+}
@@ -61,9 +61,17 @@
 //!
 //! For more examples, see [example directory](https://github.com/mvodya/passes-rs/tree/main/examples) on GitHub.
 // Primary modules
+mod error;
+pub mod order;
 mod package;
 mod pass;
+pub mod preview;
+pub mod push;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod store;
 
 // Re-exports
+pub use self::error::Error;
 pub use self::package::*;
 pub use self::pass::*;
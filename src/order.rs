@@ -0,0 +1,359 @@
+//! Wallet Orders: order.json model and `.order` packages.
+//!
+//! [Order] represents order.json, [OrderPackage] includes order and images, and writes signed `.order` file.
+//! Signing uses the same [Signer](crate::sign::Signer) as passes, with Order Type ID certificate.
+//!
+//! <https://developer.apple.com/documentation/walletorders>
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{web_service::WebService, Error};
+
+use self::fulfillment::Fulfillment;
+use self::line_item::LineItem;
+use self::merchant::{Customer, Merchant};
+use self::payment::Payment;
+
+pub mod fulfillment;
+pub mod line_item;
+pub mod merchant;
+mod package;
+pub mod payment;
+
+pub use self::package::OrderPackage;
+
+/// Required fields for [Order]
+///
+/// Used for [Order] construction
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderConfig {
+    /// The order type identifier that’s registered with Apple.
+    /// The value must be the same as the certificate used to sign the order.
+    pub order_type_identifier: String,
+
+    /// An identifier of the order, unique for the order type identifier.
+    pub order_identifier: String,
+
+    /// The URL of the order on merchant’s website, where customer can manage the order.
+    #[serde(rename = "orderManagementURL")]
+    pub order_management_url: String,
+
+    /// Merchant, who fulfills the order.
+    pub merchant: Merchant,
+}
+
+/// Represents an order (order.json file)
+///
+/// Example for making [Order] with [OrderConfig]:
+///
+/// ```
+/// use passes::order::{merchant::Merchant, OrderBuilder, OrderConfig, OrderStatus};
+///
+/// let order = OrderBuilder::new(OrderConfig {
+///     order_type_identifier: String::from("com.example.order"),
+///     order_identifier: String::from("ORDER-1234"),
+///     order_management_url: String::from("https://example.com/orders/1234"),
+///     merchant: Merchant {
+///         merchant_identifier: String::from("merchant.com.example"),
+///         display_name: String::from("Example Store"),
+///         url: String::from("https://example.com"),
+///         logo: Some(String::from("logo.png")),
+///         business_chat_url: None,
+///     },
+/// })
+/// .status(OrderStatus::Open)
+/// .build();
+/// ```
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Order {
+    /// The version of the order schema. The value must be 1.
+    schema_version: u32,
+
+    /// Primary required fields, specified for order.json
+    #[serde(flatten)]
+    pub config: OrderConfig,
+
+    /// The order number, displayed to customer.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_number: Option<String>,
+
+    /// The date and time when the order was created.
+    #[serde(with = "crate::pass::date_format::required")]
+    pub created_at: DateTime<Utc>,
+
+    /// The date and time when the order was last updated.
+    #[serde(with = "crate::pass::date_format::required")]
+    pub updated_at: DateTime<Utc>,
+
+    /// The status of the order.
+    pub status: OrderStatus,
+
+    /// The localized description of the status.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_description: Option<String>,
+
+    /// Web service for order updates, same protocol as for passes.
+    #[serde(default)]
+    #[serde(flatten)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_service: Option<WebService>,
+
+    /// Customer, who placed the order.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<Customer>,
+
+    /// Products or services in the order.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub line_items: Vec<LineItem>,
+
+    /// Shipping and pickup fulfillments of the order.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fulfillments: Vec<Fulfillment>,
+
+    /// Payment details of the order.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment: Option<Payment>,
+}
+
+/// Status of order
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderStatus {
+    /// Order is in progress
+    Open,
+
+    /// Order is fulfilled
+    Completed,
+
+    /// Order is cancelled
+    Cancelled,
+}
+
+impl Order {
+    /// Build JSON output for order (order.json)
+    pub fn make_json(&self) -> Result<String, serde_json::Error> {
+        let json = serde_json::to_string_pretty(&self)?;
+        Ok(json)
+    }
+
+    /// Build order (order.json) from json data
+    pub fn from_json(data: &str) -> Result<Self, Error> {
+        let order: Order = serde_json::from_str(data).map_err(|source| Error::Json {
+            file: String::from("order.json"),
+            source,
+        })?;
+        Ok(order)
+    }
+}
+
+/// Builder for order (represents order.json file)
+pub struct OrderBuilder {
+    order: Order,
+}
+
+impl OrderBuilder {
+    /// Creates builder for `Order`.
+    ///
+    /// [created_at](Order::created_at) and [updated_at](Order::updated_at) are set to current time.
+    pub fn new(config: OrderConfig) -> Self {
+        let now = Utc::now();
+        let order = Order {
+            // setup required vars
+            schema_version: 1,
+            config,
+            created_at: now,
+            updated_at: now,
+            status: OrderStatus::Open,
+            // Setup default optional vars
+            order_number: None,
+            status_description: None,
+            web_service: None,
+            customer: None,
+            line_items: Vec::new(),
+            fulfillments: Vec::new(),
+            payment: None,
+        };
+        Self { order }
+    }
+
+    /// Adding [order_number](Order::order_number)
+    pub fn order_number(mut self, field: String) -> OrderBuilder {
+        self.order.order_number = Some(field);
+        self
+    }
+
+    /// Adding [created_at](Order::created_at)
+    pub fn created_at(mut self, field: DateTime<Utc>) -> OrderBuilder {
+        self.order.created_at = field;
+        self
+    }
+
+    /// Adding [updated_at](Order::updated_at)
+    pub fn updated_at(mut self, field: DateTime<Utc>) -> OrderBuilder {
+        self.order.updated_at = field;
+        self
+    }
+
+    /// Adding [status](Order::status)
+    pub fn status(mut self, field: OrderStatus) -> OrderBuilder {
+        self.order.status = field;
+        self
+    }
+
+    /// Adding [status_description](Order::status_description)
+    pub fn status_description(mut self, field: String) -> OrderBuilder {
+        self.order.status_description = Some(field);
+        self
+    }
+
+    /// Adding [web_service](Order::web_service)
+    pub fn web_service(mut self, web_service: WebService) -> OrderBuilder {
+        self.order.web_service = Some(web_service);
+        self
+    }
+
+    /// Adding [customer](Order::customer)
+    pub fn customer(mut self, field: Customer) -> OrderBuilder {
+        self.order.customer = Some(field);
+        self
+    }
+
+    /// Adding [LineItem] to [line_items](Order::line_items)
+    pub fn add_line_item(mut self, item: LineItem) -> OrderBuilder {
+        self.order.line_items.push(item);
+        self
+    }
+
+    /// Adding [Fulfillment] to [fulfillments](Order::fulfillments)
+    pub fn add_fulfillment(mut self, fulfillment: Fulfillment) -> OrderBuilder {
+        self.order.fulfillments.push(fulfillment);
+        self
+    }
+
+    /// Adding [payment](Order::payment)
+    pub fn payment(mut self, field: Payment) -> OrderBuilder {
+        self.order.payment = Some(field);
+        self
+    }
+
+    /// Makes `Order`.
+    pub fn build(self) -> Order {
+        self.order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::prelude::*;
+
+    use super::{
+        fulfillment::{ShippingFulfillment, ShippingStatus},
+        payment::{CurrencyAmount, PaymentStatus},
+        *,
+    };
+
+    fn make_config() -> OrderConfig {
+        OrderConfig {
+            order_type_identifier: String::from("com.example.order"),
+            order_identifier: String::from("ORDER-1234"),
+            order_management_url: String::from("https://example.com/orders/1234"),
+            merchant: Merchant {
+                merchant_identifier: String::from("merchant.com.example"),
+                display_name: String::from("Example Store"),
+                url: String::from("https://example.com"),
+                logo: Some(String::from("logo.png")),
+                business_chat_url: None,
+            },
+        }
+    }
+
+    #[test]
+    fn make_minimal_order() {
+        let date = Utc.with_ymd_and_hms(2024, 2, 7, 10, 15, 0).unwrap();
+        let order = OrderBuilder::new(make_config())
+            .created_at(date)
+            .updated_at(date)
+            .build();
+
+        let json = order.make_json().unwrap();
+
+        let json_expected = r#"{
+  "schemaVersion": 1,
+  "orderTypeIdentifier": "com.example.order",
+  "orderIdentifier": "ORDER-1234",
+  "orderManagementURL": "https://example.com/orders/1234",
+  "merchant": {
+    "merchantIdentifier": "merchant.com.example",
+    "displayName": "Example Store",
+    "url": "https://example.com",
+    "logo": "logo.png"
+  },
+  "createdAt": "2024-02-07T10:15:00+00:00",
+  "updatedAt": "2024-02-07T10:15:00+00:00",
+  "status": "open"
+}"#;
+
+        assert_eq!(json_expected, json);
+
+        // Deserialization test
+        let order = Order::from_json(json_expected).unwrap();
+        assert_eq!(json_expected, order.make_json().unwrap());
+    }
+
+    #[test]
+    fn make_order() {
+        let date = Utc.with_ymd_and_hms(2024, 2, 7, 10, 15, 0).unwrap();
+        let mut item = LineItem::new("Coffee beans");
+        item.quantity = Some(2.0);
+        item.price = Some(CurrencyAmount::new("12.99", "USD"));
+
+        let mut shipping = ShippingFulfillment::new("SHIP-1", ShippingStatus::OnTheWay);
+        shipping.carrier = Some(String::from("UPS"));
+        shipping.tracking_url = Some(String::from("https://example.com/track/1"));
+        shipping.line_items.push(item.clone());
+
+        let order = OrderBuilder::new(make_config())
+            .order_number(String::from("1234"))
+            .created_at(date)
+            .updated_at(date)
+            .status(OrderStatus::Open)
+            .add_line_item(item)
+            .add_fulfillment(Fulfillment::Shipping(shipping))
+            .payment(Payment {
+                total: CurrencyAmount::new("25.98", "USD"),
+                status: PaymentStatus::Paid,
+                summary_items: vec![],
+                payment_methods: vec![],
+            })
+            .build();
+
+        let json = order.make_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!("shipping", value["fulfillments"][0]["fulfillmentType"]);
+        assert_eq!("onTheWay", value["fulfillments"][0]["status"]);
+        assert_eq!(
+            "https://example.com/track/1",
+            value["fulfillments"][0]["trackingURL"]
+        );
+        assert_eq!("12.99", value["lineItems"][0]["price"]["amount"]);
+        assert_eq!("paid", value["payment"]["status"]);
+
+        // Round-trip
+        let order = Order::from_json(&json).unwrap();
+        assert_eq!(json, order.make_json().unwrap());
+    }
+
+    #[test]
+    fn read_invalid_order() {
+        let err = Order::from_json(r#"{"schemaVersion": 1}"#).err().unwrap();
+        assert!(matches!(err, Error::Json { ref file, .. } if file == "order.json"));
+    }
+}
@@ -0,0 +1,238 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::line_item::LineItem;
+
+/// Fulfillment of the order (or part of it), by shipping or pickup.
+///
+/// <https://developer.apple.com/documentation/walletorders/order/fulfillments-data.dictionary>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "fulfillmentType")]
+#[serde(rename_all = "camelCase")]
+pub enum Fulfillment {
+    /// Items are shipped or delivered to customer
+    Shipping(ShippingFulfillment),
+
+    /// Customer picks items up
+    Pickup(PickupFulfillment),
+}
+
+/// Shipping or delivery of items.
+///
+/// <https://developer.apple.com/documentation/walletorders/shippingfulfillment>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShippingFulfillment {
+    /// An identifier of the fulfillment, unique within the order.
+    pub fulfillment_identifier: String,
+
+    /// The status of the fulfillment.
+    pub status: ShippingStatus,
+
+    /// The localized description of the status.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_description: Option<String>,
+
+    /// The name of the carrier, e.g. "UPS".
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub carrier: Option<String>,
+
+    /// The tracking number of the shipment.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracking_number: Option<String>,
+
+    /// The URL of the carrier’s tracking page.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "trackingURL")]
+    pub tracking_url: Option<String>,
+
+    /// The date and time when the shipment is expected to be delivered.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "crate::pass::date_format")]
+    pub estimated_delivery_at: Option<DateTime<Utc>>,
+
+    /// The date and time when the shipment was delivered.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "crate::pass::date_format")]
+    pub delivered_at: Option<DateTime<Utc>>,
+
+    /// Items of the order in this fulfillment.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub line_items: Vec<LineItem>,
+
+    /// The localized notes for customer.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl ShippingFulfillment {
+    /// Create fulfillment with identifier and status
+    pub fn new(fulfillment_identifier: &str, status: ShippingStatus) -> Self {
+        Self {
+            fulfillment_identifier: String::from(fulfillment_identifier),
+            status,
+            status_description: None,
+            carrier: None,
+            tracking_number: None,
+            tracking_url: None,
+            estimated_delivery_at: None,
+            delivered_at: None,
+            line_items: Vec::new(),
+            notes: None,
+        }
+    }
+}
+
+/// Status of shipping
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ShippingStatus {
+    /// Shipping isn't started
+    Open,
+
+    /// Items are being prepared
+    Processing,
+
+    /// Items are handed to carrier
+    Shipped,
+
+    /// Items are on the way
+    OnTheWay,
+
+    /// Items are out for delivery
+    OutForDelivery,
+
+    /// Items are delivered
+    Delivered,
+
+    /// Delivery has a problem
+    Issue,
+
+    /// Shipping is cancelled
+    Cancelled,
+}
+
+/// Pickup of items by customer.
+///
+/// <https://developer.apple.com/documentation/walletorders/pickupfulfillment>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PickupFulfillment {
+    /// An identifier of the fulfillment, unique within the order.
+    pub fulfillment_identifier: String,
+
+    /// The status of the fulfillment.
+    pub status: PickupStatus,
+
+    /// The localized description of the status.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_description: Option<String>,
+
+    /// The localized name of the pickup location.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    /// The address of the pickup location.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+
+    /// The date and time when items are ready for pickup.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "crate::pass::date_format")]
+    pub pickup_at: Option<DateTime<Utc>>,
+
+    /// The date and time when customer picked items up.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "crate::pass::date_format")]
+    pub picked_up_at: Option<DateTime<Utc>>,
+
+    /// Items of the order in this fulfillment.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub line_items: Vec<LineItem>,
+
+    /// The localized notes for customer, e.g. pickup instructions.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl PickupFulfillment {
+    /// Create fulfillment with identifier and status
+    pub fn new(fulfillment_identifier: &str, status: PickupStatus) -> Self {
+        Self {
+            fulfillment_identifier: String::from(fulfillment_identifier),
+            status,
+            status_description: None,
+            display_name: None,
+            address: None,
+            pickup_at: None,
+            picked_up_at: None,
+            line_items: Vec::new(),
+            notes: None,
+        }
+    }
+}
+
+/// Status of pickup
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PickupStatus {
+    /// Pickup isn't started
+    Open,
+
+    /// Items are being prepared
+    Processing,
+
+    /// Items are ready for pickup
+    ReadyForPickup,
+
+    /// Customer picked items up
+    PickedUp,
+
+    /// Pickup is cancelled
+    Cancelled,
+}
+
+/// Postal address
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Address {
+    /// Street lines of the address.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub address_lines: Vec<String>,
+
+    /// The city.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locality: Option<String>,
+
+    /// The state or province.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub administrative_area: Option<String>,
+
+    /// The postal code.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+
+    /// The ISO 3166-1 alpha-2 country code.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use super::payment::CurrencyAmount;
+
+/// Product or service in the order.
+///
+/// <https://developer.apple.com/documentation/walletorders/lineitem>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LineItem {
+    /// The localized title of the item.
+    pub title: String,
+
+    /// The localized subtitle of the item, e.g. size or color.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+
+    /// The number of units of the item.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<f64>,
+
+    /// The price of one unit of the item.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<CurrencyAmount>,
+
+    /// Name of image file in order package for the item.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+
+    /// The stock keeping unit of the item.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sku: Option<String>,
+
+    /// The Global Trade Item Number of the item.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gtin: Option<String>,
+}
+
+impl LineItem {
+    /// Create item with title
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: String::from(title),
+            subtitle: None,
+            quantity: None,
+            price: None,
+            image: None,
+            sku: None,
+            gtin: None,
+        }
+    }
+}
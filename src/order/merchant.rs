@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// Merchant, who fulfills the order.
+///
+/// <https://developer.apple.com/documentation/walletorders/merchant>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Merchant {
+    /// An identifier for the merchant, e.g. Apple Pay merchant identifier.
+    pub merchant_identifier: String,
+
+    /// The localized display name of the merchant.
+    pub display_name: String,
+
+    /// The URL for the merchant’s website or landing page.
+    pub url: String,
+
+    /// Name of image file in order package for merchant logo.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo: Option<String>,
+
+    /// The URL for the merchant’s Business Chat service.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "businessChatURL")]
+    pub business_chat_url: Option<String>,
+}
+
+/// Customer, who placed the order.
+///
+/// <https://developer.apple.com/documentation/walletorders/customer>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Customer {
+    /// The customer’s given name.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+
+    /// The customer’s family name.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+
+    /// The customer’s email address.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_address: Option<String>,
+
+    /// The customer’s phone number.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone_number: Option<String>,
+}
@@ -0,0 +1,248 @@
+use std::{
+    collections::BTreeMap,
+    io::{Read, Seek, Write},
+};
+
+use crate::{
+    manifest::{Algorithm, Manifest},
+    package::write_file,
+    sign::{SignConfig, Signer},
+    Error,
+};
+
+use super::Order;
+
+/// Order Package, contains order.json, images, manifest.json and signature.
+///
+/// Manifest of order uses SHA-256 checksums.
+///
+/// ```
+/// use passes::order::{merchant::Merchant, OrderBuilder, OrderConfig, OrderPackage};
+///
+/// let order = OrderBuilder::new(OrderConfig {
+///     order_type_identifier: String::from("com.example.order"),
+///     order_identifier: String::from("ORDER-1234"),
+///     order_management_url: String::from("https://example.com/orders/1234"),
+///     merchant: Merchant {
+///         merchant_identifier: String::from("merchant.com.example"),
+///         display_name: String::from("Example Store"),
+///         url: String::from("https://example.com"),
+///         logo: Some(String::from("logo.png")),
+///         business_chat_url: None,
+///     },
+/// })
+/// .build();
+///
+/// let mut package = OrderPackage::new(order);
+/// package.add_resource("logo.png", &[0u8; 16][..]).unwrap();
+///
+/// // Save package as .order
+/// let mut buf = std::io::Cursor::new(Vec::new());
+/// package.write(&mut buf).unwrap();
+/// ```
+pub struct OrderPackage {
+    /// Represents order.json
+    pub order: Order,
+
+    /// Resources (image files, localized `.lproj` files) by path in package
+    pub resources: BTreeMap<String, Vec<u8>>,
+
+    /// Manifest (manifest.json), read from existing package.
+    ///
+    /// Not used for writing, new manifest is generated from package contents.
+    pub manifest: Option<Manifest>,
+
+    /// Signer for package, makes signature file
+    pub signer: Option<Box<dyn Signer>>,
+}
+
+impl OrderPackage {
+    /// Create new package
+    pub fn new(order: Order) -> Self {
+        Self {
+            order,
+            resources: BTreeMap::new(),
+            manifest: None,
+            signer: None,
+        }
+    }
+
+    /// Read compressed package (.order) from file.
+    pub fn read<R: Read + Seek>(reader: R) -> Result<Self, Error> {
+        let mut zip =
+            zip::ZipArchive::new(reader).map_err(|source| Error::Zip { file: None, source })?;
+
+        let mut order: Option<Order> = None;
+        let mut manifest: Option<Manifest> = None;
+        let mut resources = BTreeMap::new();
+
+        for i in 0..zip.len() {
+            let mut file = zip
+                .by_index(i)
+                .map_err(|source| Error::Zip { file: None, source })?;
+            let filename = file.name().to_string();
+            if file.is_dir() || filename == "signature" {
+                continue;
+            }
+
+            let mut buf = Vec::new();
+            file.read_to_end(&mut buf).map_err(|source| Error::Io {
+                file: Some(filename.clone()),
+                source,
+            })?;
+            match filename.as_str() {
+                "order.json" => {
+                    let json = String::from_utf8_lossy(&buf);
+                    order = Some(Order::from_json(&json)?);
+                }
+                "manifest.json" => {
+                    let json = String::from_utf8_lossy(&buf);
+                    manifest = Some(Manifest::from_json(&json)?);
+                }
+                _ => {
+                    resources.insert(filename, buf);
+                }
+            }
+        }
+
+        // Check is order.json successfully read
+        let Some(order) = order else {
+            return Err(Error::MissingFile {
+                file: String::from("order.json"),
+            });
+        };
+        Ok(Self {
+            order,
+            resources,
+            manifest,
+            signer: None,
+        })
+    }
+
+    /// Adding resource file (e.g. merchant logo) to package.
+    ///
+    /// `path` is a name of file in package, that is referenced from order.json.
+    pub fn add_resource<R: Read>(&mut self, path: &str, mut reader: R) -> Result<(), Error> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).map_err(|source| Error::Io {
+            file: Some(String::from(path)),
+            source,
+        })?;
+        self.resources.insert(String::from(path), data);
+        Ok(())
+    }
+
+    /// Add certificates for signing package
+    pub fn add_certificates(&mut self, config: SignConfig) {
+        self.signer = Some(Box::new(config));
+    }
+
+    /// Add custom signer for package, e.g. backed by HSM or KMS
+    pub fn add_signer(&mut self, signer: Box<dyn Signer>) {
+        self.signer = Some(signer);
+    }
+
+    /// Write compressed package.
+    ///
+    /// Use for creating .order file
+    pub fn write<W: Write + Seek>(&self, writer: W) -> Result<(), Error> {
+        let mut manifest = Manifest::with_algorithm(Algorithm::Sha256);
+
+        let mut zip = zip::ZipWriter::new(writer);
+
+        // Adding order.json to zip
+        let order_json = self.order.make_json().map_err(|source| Error::Json {
+            file: String::from("order.json"),
+            source,
+        })?;
+        write_file(&mut zip, "order.json", order_json.as_bytes())?;
+        manifest.add_item("order.json", order_json.as_bytes());
+
+        // Adding each resource files to zip
+        for (path, data) in &self.resources {
+            write_file(&mut zip, path, data)?;
+            manifest.add_item(path, data);
+        }
+
+        // Adding manifest.json to zip
+        let manifest_json = manifest.make_json().map_err(|source| Error::Json {
+            file: String::from("manifest.json"),
+            source,
+        })?;
+        write_file(&mut zip, "manifest.json", manifest_json.as_bytes())?;
+
+        // If signer is provided, make signature
+        if let Some(signer) = &self.signer {
+            let signature_data = signer.sign(manifest_json.as_bytes())?;
+            write_file(&mut zip, "signature", &signature_data)?;
+        }
+
+        zip.finish()
+            .map_err(|source| Error::Zip { file: None, source })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{
+        order::{merchant::Merchant, OrderBuilder, OrderConfig},
+        sign::TestSigner,
+    };
+
+    use super::*;
+
+    #[test]
+    fn write_and_read_order_package() {
+        let order = OrderBuilder::new(OrderConfig {
+            order_type_identifier: String::from("com.example.order"),
+            order_identifier: String::from("ORDER-1234"),
+            order_management_url: String::from("https://example.com/orders/1234"),
+            merchant: Merchant {
+                merchant_identifier: String::from("merchant.com.example"),
+                display_name: String::from("Example Store"),
+                url: String::from("https://example.com"),
+                logo: Some(String::from("logo.png")),
+                business_chat_url: None,
+            },
+        })
+        .build();
+        let expected_json = order.make_json().unwrap();
+
+        let mut package = OrderPackage::new(order);
+        package.add_resource("logo.png", &[0u8; 64][..]).unwrap();
+        package.add_signer(Box::new(TestSigner::new().unwrap()));
+
+        let mut buf = Cursor::new(Vec::new());
+        package.write(&mut buf).unwrap();
+        let data = buf.into_inner();
+
+        // Signature is present
+        let mut zip = zip::ZipArchive::new(Cursor::new(&data)).unwrap();
+        assert!(zip.by_name("signature").is_ok());
+
+        let package = OrderPackage::read(Cursor::new(&data)).unwrap();
+        assert_eq!(expected_json, package.order.make_json().unwrap());
+        assert_eq!(vec![0u8; 64], package.resources["logo.png"]);
+
+        // Manifest has SHA-256 of every file
+        let manifest = package.manifest.unwrap();
+        assert_eq!(Algorithm::Sha256, manifest.algorithm());
+        assert_eq!(2, manifest.len());
+        assert!(manifest.verify_item("order.json", expected_json.as_bytes()));
+        assert!(manifest.verify_item("logo.png", &[0u8; 64]));
+    }
+
+    #[test]
+    fn read_package_without_order_json() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        write_file(&mut zip, "logo.png", &[0u8; 32]).unwrap();
+        let buf = zip.finish().unwrap().into_inner();
+
+        let err = OrderPackage::read(Cursor::new(buf)).err().unwrap();
+        assert!(matches!(err, Error::MissingFile { ref file } if file == "order.json"));
+    }
+}
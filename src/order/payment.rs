@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+/// Amount of money in currency.
+///
+/// <https://developer.apple.com/documentation/walletorders/currencyamount>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyAmount {
+    /// The amount of money as decimal number, e.g. `"12.99"`.
+    pub amount: String,
+
+    /// The ISO 4217 currency code, e.g. `"USD"`.
+    pub currency: String,
+}
+
+impl CurrencyAmount {
+    /// Create amount in currency
+    pub fn new(amount: &str, currency: &str) -> Self {
+        Self {
+            amount: String::from(amount),
+            currency: String::from(currency),
+        }
+    }
+}
+
+/// Payment details of the order.
+///
+/// <https://developer.apple.com/documentation/walletorders/payment>
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Payment {
+    /// The total amount of the order.
+    pub total: CurrencyAmount,
+
+    /// The status of the payment.
+    pub status: PaymentStatus,
+
+    /// Items of payment summary, such as subtotal, shipping, taxes.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub summary_items: Vec<SummaryItem>,
+
+    /// Payment methods, used for the order.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub payment_methods: Vec<PaymentMethod>,
+}
+
+/// Status of payment
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PaymentStatus {
+    /// Payment isn't processed yet
+    Pending,
+
+    /// Payment is authorized
+    Authorized,
+
+    /// Payment is complete
+    Paid,
+
+    /// Payment is refunded
+    Refunded,
+}
+
+/// Item of payment summary
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryItem {
+    /// The localized label of the item, e.g. "Shipping".
+    pub label: String,
+
+    /// The amount of the item.
+    pub value: CurrencyAmount,
+}
+
+/// Payment method, used for the order
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentMethod {
+    /// The localized name of the payment method, e.g. "Visa •••• 1234".
+    pub display_name: String,
+}
@@ -1,15 +1,31 @@
 use std::{
-    io::{Read, Seek, Write},
+    collections::BTreeMap,
+    fs,
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
     str::FromStr,
 };
 
-use crate::pass::Pass;
+use crate::{
+    pass::{fields::ImageSet, validation::Diagnostic, Pass},
+    Error,
+};
 
-use self::{manifest::Manifest, resource::Resource, sign::SignConfig};
+use self::{
+    localization::Localization,
+    manifest::Manifest,
+    personalization::Personalization,
+    resource::Resource,
+    sign::{SignConfig, Signer, WWDR},
+};
 
+pub mod bundle;
+pub mod localization;
 pub mod manifest;
+pub mod personalization;
 pub mod resource;
 pub mod sign;
+pub mod template;
 
 /// Pass Package, contains information about pass.json, images, manifest.json and signature.
 pub struct Package {
@@ -19,8 +35,22 @@ pub struct Package {
     /// Resources (image files)
     pub resources: Vec<Resource>,
 
-    // Certificates for signing package
-    pub sign_config: Option<SignConfig>,
+    /// Localizations (`<language>.lproj` folders) by language code
+    pub localizations: BTreeMap<String, Localization>,
+
+    /// Personalization (personalization.json), makes pass personalizable
+    pub personalization: Option<Personalization>,
+
+    /// Manifest (manifest.json), read from existing package.
+    ///
+    /// Not used for writing, new manifest is generated from package contents.
+    pub manifest: Option<Manifest>,
+
+    /// Signer for package, makes signature file
+    pub signer: Option<Box<dyn Signer>>,
+
+    /// Problems found on reading package, reported by [validate](Package::validate)
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl Package {
@@ -29,134 +59,296 @@ impl Package {
         Self {
             pass,
             resources: vec![],
-            sign_config: None,
+            localizations: BTreeMap::new(),
+            personalization: None,
+            manifest: None,
+            signer: None,
+            diagnostics: vec![],
         }
     }
 
     /// Read compressed package (.pkpass) from file.
     ///
     /// Use for creating .pkpass file from template.
-    pub fn read<R: Read + Seek>(reader: R) -> Result<Self, &'static str> {
+    ///
+    /// Malformed manifest.json, personalization.json and pass.strings files are skipped
+    /// and reported by [validate](Package::validate), use [verify](Package::verify) to reject such packages.
+    pub fn read<R: Read + Seek>(reader: R) -> Result<Self, Error> {
         // Read .pkpass as zip
-        let mut zip = zip::ZipArchive::new(reader).expect("Error unzipping pkpass");
+        let mut zip =
+            zip::ZipArchive::new(reader).map_err(|source| Error::Zip { file: None, source })?;
 
-        let mut pass: Option<Pass> = None;
-        let mut resources = Vec::<Resource>::new();
+        let mut contents = Contents::default();
 
         for i in 0..zip.len() {
             // Get file name
-            let mut file = zip.by_index(i).unwrap();
-            let filename = file.name();
-            // Read pass.json file
-            if filename == "pass.json" {
-                let mut buf = String::new();
-                file.read_to_string(&mut buf)
-                    .expect("Error while reading pass.json");
-                pass = Some(Pass::from_json(&buf).expect("Error while parsing pass.json"));
+            let mut file = zip
+                .by_index(i)
+                .map_err(|source| Error::Zip { file: None, source })?;
+            let filename = file.name().to_string();
+            if file.is_dir() {
                 continue;
             }
-            // Read resource files
-            match resource::Type::from_str(filename) {
-                // Match resource type by template
-                Ok(t) => {
-                    let mut resource = Resource::new(t);
-                    std::io::copy(&mut file, &mut resource)
-                        .expect("Error while reading resource file");
-                    resources.push(resource);
-                }
-                // Skip unknown files
-                Err(_) => {}
+
+            let mut buf = Vec::new();
+            file.read_to_end(&mut buf).map_err(|source| Error::Io {
+                file: Some(filename.clone()),
+                source,
+            })?;
+            // Skip unknown files
+            contents.add_file(&filename, buf)?;
+        }
+
+        contents.into_package()
+    }
+
+    /// Read package from unpacked directory with pass.json, images and `<language>.lproj` folders.
+    ///
+    /// Files are matched the same way as in [read](Package::read). Returns package
+    /// and paths (relative to directory) of unknown files, which are not included in package.
+    ///
+    /// ```no_run
+    /// use passes::Package;
+    ///
+    /// let (package, unknown_files) = Package::from_dir("pass").unwrap();
+    /// for path in unknown_files {
+    ///     eprintln!("skipping unknown file {}", path);
+    /// }
+    /// ```
+    pub fn from_dir<P: AsRef<Path>>(path: P) -> Result<(Self, Vec<String>), Error> {
+        let mut files = Vec::new();
+        list_files(path.as_ref(), "", &mut files)?;
+
+        let mut contents = Contents::default();
+        let mut unknown_files = Vec::new();
+        for (filename, path) in files {
+            let data = fs::read(&path).map_err(|source| Error::Io {
+                file: Some(path.display().to_string()),
+                source,
+            })?;
+            if !contents.add_file(&filename, data)? {
+                unknown_files.push(filename);
             }
         }
 
-        // Check is pass.json successfully read
-        if let Some(pass) = pass {
-            Ok(Self {
-                pass,
-                resources,
-                sign_config: None,
-            })
-        } else {
-            Err("pass.json is missed in package file")
+        Ok((contents.into_package()?, unknown_files))
+    }
+
+    /// Read compressed package (.pkpass) from file and verify it.
+    ///
+    /// Same as [read](Package::read), but fails if package doesn't pass [verify](Package::verify).
+    pub fn read_verified<R: Read + Seek>(mut reader: R, wwdr: WWDR) -> Result<Self, Error> {
+        Self::verify(&mut reader, wwdr)?;
+        reader
+            .seek(SeekFrom::Start(0))
+            .map_err(|source| Error::Io { file: None, source })?;
+        Self::read(reader)
+    }
+
+    /// Verify compressed package (.pkpass) integrity and signature.
+    ///
+    /// Checks that:
+    /// * every file in package is listed in manifest.json, and its SHA-1 checksum matches;
+    /// * every file listed in manifest.json is present in package;
+    /// * signature is a valid detached PKCS#7 signature of manifest.json, made by certificate issued by `wwdr`.
+    ///
+    /// Use for rejecting tampered passes, received from third parties.
+    pub fn verify<R: Read + Seek>(reader: R, wwdr: WWDR) -> Result<(), Error> {
+        // Read .pkpass as zip
+        let mut zip =
+            zip::ZipArchive::new(reader).map_err(|source| Error::Zip { file: None, source })?;
+
+        // Read all files from package
+        let mut files = BTreeMap::<String, Vec<u8>>::new();
+        for i in 0..zip.len() {
+            let mut file = zip
+                .by_index(i)
+                .map_err(|source| Error::Zip { file: None, source })?;
+            if file.is_dir() {
+                continue;
+            }
+            let filename = file.name().to_string();
+            let mut buf = Vec::new();
+            file.read_to_end(&mut buf).map_err(|source| Error::Io {
+                file: Some(filename.clone()),
+                source,
+            })?;
+            files.insert(filename, buf);
         }
+
+        let manifest_json = files.remove("manifest.json").ok_or(Error::MissingFile {
+            file: String::from("manifest.json"),
+        })?;
+        let signature = files.remove("signature").ok_or(Error::MissingFile {
+            file: String::from("signature"),
+        })?;
+
+        // Check signature of manifest.json
+        sign::verify(wwdr, &manifest_json, &signature)?;
+
+        // Check files checksums
+        let manifest: Manifest =
+            serde_json::from_slice(&manifest_json).map_err(|source| Error::Json {
+                file: String::from("manifest.json"),
+                source,
+            })?;
+        for (filename, data) in &files {
+            if manifest.get(filename).is_none() {
+                return Err(Error::UnexpectedFile {
+                    file: filename.clone(),
+                });
+            }
+            if !manifest.verify_item(filename, data) {
+                return Err(Error::ChecksumMismatch {
+                    file: filename.clone(),
+                });
+            }
+        }
+
+        // Files, listed in manifest, but missed in package
+        if let Some(item) = manifest.iter().find(|item| !files.contains_key(&item.path)) {
+            return Err(Error::MissingFile {
+                file: item.path.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Add localization for language, e.g. `en`, `ru` or `zh-Hans`.
+    ///
+    /// Replaces existing localization for the same language.
+    pub fn add_localization(&mut self, language: &str, localization: Localization) {
+        self.localizations
+            .insert(String::from(language), localization);
+    }
+
+    /// Get localization for language, creates empty one if missed
+    pub fn localization_mut(&mut self, language: &str) -> &mut Localization {
+        self.localizations
+            .entry(String::from(language))
+            .or_default()
     }
 
     /// Add certificates for signing package
     pub fn add_certificates(&mut self, config: SignConfig) {
-        self.sign_config = Some(config);
+        self.signer = Some(Box::new(config));
+    }
+
+    /// Add custom signer for package, e.g. backed by HSM or KMS
+    pub fn add_signer(&mut self, signer: Box<dyn Signer>) {
+        self.signer = Some(signer);
     }
 
     /// Write compressed package.
     ///
     /// Use for creating .pkpass file
-    pub fn write<W: Write + Seek>(&mut self, writer: W) -> Result<(), &'static str> {
+    pub fn write<W: Write + Seek>(&mut self, writer: W) -> Result<(), Error> {
+        self.write_signed(writer, self.signer.as_deref())
+    }
+
+    /// Write compressed package, signed by `signer` instead of package signer
+    fn write_signed<W: Write + Seek>(
+        &self,
+        writer: W,
+        signer: Option<&dyn Signer>,
+    ) -> Result<(), Error> {
         let mut manifest = Manifest::new();
 
         let mut zip = zip::ZipWriter::new(writer);
-        let options =
-            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
 
         // Adding pass.json to zip
-        zip.start_file("pass.json", options)
-            .expect("Error while creating pass.json in zip");
-        let pass_json = self
-            .pass
-            .make_json()
-            .expect("Error while building pass.json");
-        zip.write_all(pass_json.as_bytes())
-            .expect("Error while writing pass.json in zip");
+        let pass_json = self.pass.make_json().map_err(|source| Error::Json {
+            file: String::from("pass.json"),
+            source,
+        })?;
+        write_file(&mut zip, "pass.json", pass_json.as_bytes())?;
         manifest.add_item("pass.json", pass_json.as_bytes());
 
         // Adding each resource files to zip
         for resource in &self.resources {
-            zip.start_file(resource.filename(), options)
-                .expect("Error while creating resource file in zip");
-            zip.write_all(resource.as_bytes())
-                .expect("Error while writing resource file in zip");
+            write_file(&mut zip, &resource.filename(), resource.as_bytes())?;
             manifest.add_item(resource.filename().as_str(), resource.as_bytes());
         }
 
+        // Adding personalization.json to zip
+        if let Some(personalization) = &self.personalization {
+            let json = make_personalization_json(personalization)?;
+            write_file(&mut zip, "personalization.json", json.as_bytes())?;
+            manifest.add_item("personalization.json", json.as_bytes());
+        }
+
+        // Adding localized files to zip
+        for (language, localization) in &self.localizations {
+            let folder = localization::folder_name(language);
+            if !localization.strings.is_empty() {
+                let path = format!("{}/pass.strings", folder);
+                let data = localization.make_strings();
+                write_file(&mut zip, &path, &data)?;
+                manifest.add_item(&path, &data);
+            }
+            for resource in &localization.resources {
+                let path = format!("{}/{}", folder, resource.filename());
+                write_file(&mut zip, &path, resource.as_bytes())?;
+                manifest.add_item(&path, resource.as_bytes());
+            }
+        }
+
         // Adding manifest.json to zip
-        zip.start_file("manifest.json", options)
-            .expect("Error while creating manifest.json in zip");
-        let manifest_json = manifest
-            .make_json()
-            .expect("Error while generating manifest file");
-        zip.write_all(manifest_json.as_bytes())
-            .expect("Error while writing manifest.json in zip");
+        let manifest_json = manifest.make_json().map_err(|source| Error::Json {
+            file: String::from("manifest.json"),
+            source,
+        })?;
+        write_file(&mut zip, "manifest.json", manifest_json.as_bytes())?;
         manifest.add_item("manifest.json", manifest_json.as_bytes());
 
-        // If SignConfig is provided, make signature
-        if let Some(sign_config) = &self.sign_config {
-            // Make signature without signing content
-            let flags = openssl::pkcs7::Pkcs7Flags::DETACHED;
-            // Add WWDR cert to chain
-            let mut certs = openssl::stack::Stack::new().expect("Error while prepare certificate");
-            certs
-                .push(sign_config.cert.clone())
-                .expect("Error while prepare certificate");
-
-            // Signing
-            let pkcs7 = openssl::pkcs7::Pkcs7::sign(
-                &sign_config.sign_cert,
-                &sign_config.sign_key,
-                &certs,
-                manifest_json.as_bytes(),
-                flags,
-            )
-            .expect("Error while signing package");
-
-            // Generate signature
-            let signature_data = pkcs7.to_der().expect("Error while generating signature");
+        // If signer is provided, make signature
+        if let Some(signer) = signer {
+            let signature_data = signer.sign(manifest_json.as_bytes())?;
 
             // Adding signature to zip
-            zip.start_file("signature", options)
-                .expect("Error while creating signature in zip");
-            zip.write_all(&signature_data)
-                .expect("Error while writing signature in zip");
+            write_file(&mut zip, "signature", &signature_data)?;
+        }
+
+        zip.finish()
+            .map_err(|source| Error::Zip { file: None, source })?;
+
+        Ok(())
+    }
+
+    /// Write unpacked package to directory, reverse of [from_dir](Package::from_dir).
+    ///
+    /// Writes pass.json, personalization.json, images and `<language>.lproj` folders. Manifest and signature
+    /// are not written, they are generated by [write](Package::write).
+    pub fn write_dir<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+
+        let pass_json = self.pass.make_json().map_err(|source| Error::Json {
+            file: String::from("pass.json"),
+            source,
+        })?;
+        create_dir(path)?;
+        write_dir_file(&path.join("pass.json"), pass_json.as_bytes())?;
+
+        for resource in &self.resources {
+            write_dir_file(&path.join(resource.filename()), resource.as_bytes())?;
+        }
+
+        if let Some(personalization) = &self.personalization {
+            let json = make_personalization_json(personalization)?;
+            write_dir_file(&path.join("personalization.json"), json.as_bytes())?;
         }
 
-        zip.finish().expect("Error while saving zip");
+        for (language, localization) in &self.localizations {
+            let folder = path.join(localization::folder_name(language));
+            create_dir(&folder)?;
+            if !localization.strings.is_empty() {
+                write_dir_file(&folder.join("pass.strings"), &localization.make_strings())?;
+            }
+            for resource in &localization.resources {
+                write_dir_file(&folder.join(resource.filename()), resource.as_bytes())?;
+            }
+        }
 
         Ok(())
     }
@@ -168,22 +360,360 @@ impl Package {
         &mut self,
         image_type: resource::Type,
         mut reader: R,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), Error> {
         let mut resource = Resource::new(image_type);
-        std::io::copy(&mut reader, &mut resource).expect("Error while reading resource");
+        std::io::copy(&mut reader, &mut resource).map_err(|source| Error::Io {
+            file: Some(resource.filename()),
+            source,
+        })?;
         self.resources.push(resource);
         Ok(())
     }
+
+    /// Adding PNG or JPEG image to package, image is re-encoded to PNG.
+    ///
+    /// Requires `image` feature. Returns warnings about images, larger than
+    /// [allotted space](resource::Type::allotted_pixels) for pass style.
+    #[cfg(feature = "image")]
+    pub fn add_image<R: Read>(
+        &mut self,
+        image_type: resource::Type,
+        reader: R,
+    ) -> Result<Vec<Diagnostic>, Error> {
+        let data = read_image(&image_type, reader)?;
+        let (resource, warnings) = Resource::from_image(image_type, &data, &self.pass.fields)?;
+        self.resources.push(resource);
+        Ok(warnings)
+    }
+
+    /// Adding standard, @2x and @3x images, made from one high-resolution PNG or JPEG image (used as @3x).
+    ///
+    /// Requires `image` feature. Returns warnings about images, larger than
+    /// [allotted space](resource::Type::allotted_pixels) for pass style.
+    ///
+    /// ```
+    /// use passes::{resource, Package, PassBuilder, PassConfig};
+    ///
+    /// # let mut logo = Vec::new();
+    /// # image::RgbImage::new(480, 150)
+    /// #     .write_to(&mut std::io::Cursor::new(&mut logo), image::ImageFormat::Png)
+    /// #     .unwrap();
+    /// let pass = PassBuilder::new(PassConfig {
+    ///     organization_name: String::from("Apple inc."),
+    ///     description: String::from("Example pass"),
+    ///     pass_type_identifier: String::from("com.example.pass"),
+    ///     team_identifier: String::from("AA00AA0A0A"),
+    ///     serial_number: String::from("ABCDEFG1234567890"),
+    /// })
+    /// .build();
+    ///
+    /// let mut package = Package::new(pass);
+    /// let warnings = package
+    ///     .add_image_set(resource::Type::Logo(resource::Version::Standard), &logo[..])
+    ///     .unwrap();
+    /// assert!(warnings.is_empty());
+    /// assert_eq!("logo@2x.png", package.resources[1].filename());
+    /// ```
+    #[cfg(feature = "image")]
+    pub fn add_image_set<R: Read>(
+        &mut self,
+        image_type: resource::Type,
+        reader: R,
+    ) -> Result<Vec<Diagnostic>, Error> {
+        let data = read_image(&image_type, reader)?;
+        let (resources, warnings) = Resource::from_image_set(image_type, &data, &self.pass.fields)?;
+        self.resources.extend(resources);
+        Ok(warnings)
+    }
+
+    /// Images of the package, that change layout of pass fields
+    pub fn image_set(&self) -> ImageSet {
+        let mut images = ImageSet::default();
+        let resources = self.resources.iter().chain(
+            self.localizations
+                .values()
+                .flat_map(|localization| localization.resources.iter()),
+        );
+        for resource in resources {
+            match resource.get_type() {
+                resource::Type::Background(_) => images.background = true,
+                resource::Type::Strip(_) => images.strip = true,
+                resource::Type::Thumbnail(_) => images.thumbnail = true,
+                _ => {}
+            }
+        }
+        images
+    }
+
+    /// Check pass and package contents against Apple's rules.
+    ///
+    /// In addition to [Pass::validate](crate::Pass::validate) checks that required icon.png is present
+    /// (in package root or in every localization) and reports fields, hidden for pass style and [images](Package::image_set).
+    /// With `image` feature also reports images, that are not PNG or can't be decoded, and images of wrong size:
+    /// larger than [allotted space](resource::Type::allotted_pixels) for pass style, or too small icon and thumbnail
+    /// (see [check_size](Resource::check_size)).
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = self.pass.validate();
+        diagnostics.extend(self.diagnostics.iter().cloned());
+
+        for (group, field) in self.pass.hidden_fields(&self.image_set()) {
+            diagnostics.push(Diagnostic::HiddenField {
+                group,
+                key: field.key.clone(),
+            });
+        }
+
+        let is_icon = |resource: &Resource| {
+            resource.get_type() == resource::Type::Icon(resource::Version::Standard)
+        };
+        let has_icon = self.resources.iter().any(is_icon)
+            || (!self.localizations.is_empty()
+                && self
+                    .localizations
+                    .values()
+                    .all(|localization| localization.resources.iter().any(is_icon)));
+        if !has_icon {
+            diagnostics.push(Diagnostic::MissingIcon);
+        }
+
+        #[cfg(feature = "image")]
+        {
+            let resources = self
+                .resources
+                .iter()
+                .map(|resource| (resource.filename(), resource))
+                .chain(
+                    self.localizations
+                        .iter()
+                        .flat_map(|(language, localization)| {
+                            localization.resources.iter().map(move |resource| {
+                                (
+                                    format!("{}.lproj/{}", language, resource.filename()),
+                                    resource,
+                                )
+                            })
+                        }),
+                );
+            for (path, resource) in resources {
+                match resource.check_size(&self.pass.fields) {
+                    Ok(diagnostic) => diagnostics.extend(diagnostic),
+                    // Wallet rejects images, that are not PNG or can't be decoded
+                    Err(err) => diagnostics.push(Diagnostic::InvalidFile {
+                        file: path,
+                        message: match err {
+                            Error::Parse { message, .. } => message,
+                            err => err.to_string(),
+                        },
+                    }),
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+/// Files of package, collected while reading
+#[derive(Default)]
+struct Contents {
+    pass: Option<Pass>,
+    manifest: Option<Manifest>,
+    resources: Vec<Resource>,
+    localizations: BTreeMap<String, Localization>,
+    personalization: Option<Personalization>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Contents {
+    /// Add file by path in package, returns `false` if file is unknown
+    fn add_file(&mut self, filename: &str, data: Vec<u8>) -> Result<bool, Error> {
+        match filename {
+            // Read pass.json file
+            "pass.json" => {
+                let json = String::from_utf8(data).map_err(|source| Error::Io {
+                    file: Some(String::from(filename)),
+                    source: std::io::Error::new(std::io::ErrorKind::InvalidData, source),
+                })?;
+                self.pass = Some(Pass::from_json(&json)?);
+            }
+            // Read manifest.json file
+            "manifest.json" => {
+                let json = String::from_utf8_lossy(&data);
+                match Manifest::from_json(&json) {
+                    Ok(manifest) => self.manifest = Some(manifest),
+                    Err(err) => self.invalid_file(filename, err),
+                }
+            }
+            // Read personalization.json file
+            "personalization.json" => {
+                let json = String::from_utf8_lossy(&data);
+                match Personalization::from_json(&json) {
+                    Ok(personalization) => self.personalization = Some(personalization),
+                    Err(err) => self.invalid_file(filename, err),
+                }
+            }
+            // Signature is made on writing
+            "signature" => {}
+            _ => {
+                // Read localized files (<language>.lproj folders)
+                if let Some((language, name)) = localization::split_path(filename) {
+                    if name == "pass.strings" {
+                        match localization::parse_strings_file(filename, &data) {
+                            Ok(strings) => {
+                                self.localizations
+                                    .entry(language.to_string())
+                                    .or_default()
+                                    .strings = strings
+                            }
+                            Err(err) => self.invalid_file(filename, err),
+                        }
+                    } else if let Ok(t) = resource::Type::from_str(name) {
+                        let localization =
+                            self.localizations.entry(language.to_string()).or_default();
+                        localization.resources.push(Resource::from_data(t, data));
+                    } else {
+                        return Ok(false);
+                    }
+                    return Ok(true);
+                }
+                // Read resource files, match resource type by template
+                let Ok(t) = resource::Type::from_str(filename) else {
+                    return Ok(false);
+                };
+                self.resources.push(Resource::from_data(t, data));
+            }
+        }
+        Ok(true)
+    }
+
+    /// Skip file, that can't be parsed, it's reported by [Package::validate]
+    fn invalid_file(&mut self, filename: &str, err: Error) {
+        let message = match err {
+            Error::Parse { message, .. } => message,
+            err => match std::error::Error::source(&err) {
+                Some(source) => source.to_string(),
+                None => err.to_string(),
+            },
+        };
+        self.diagnostics.push(Diagnostic::InvalidFile {
+            file: String::from(filename),
+            message,
+        });
+    }
+
+    /// Make package, pass.json is required
+    fn into_package(self) -> Result<Package, Error> {
+        let Some(pass) = self.pass else {
+            return Err(Error::MissingPassJson);
+        };
+        Ok(Package {
+            pass,
+            resources: self.resources,
+            localizations: self.localizations,
+            personalization: self.personalization,
+            manifest: self.manifest,
+            signer: None,
+            diagnostics: self.diagnostics,
+        })
+    }
+}
+
+/// Read image data for [add_image](Package::add_image)
+#[cfg(feature = "image")]
+fn read_image<R: Read>(image_type: &resource::Type, mut reader: R) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).map_err(|source| Error::Io {
+        file: Some(image_type.to_string()),
+        source,
+    })?;
+    Ok(data)
+}
+
+/// Build personalization.json
+fn make_personalization_json(personalization: &Personalization) -> Result<String, Error> {
+    personalization.make_json().map_err(|source| Error::Json {
+        file: String::from("personalization.json"),
+        source,
+    })
+}
+
+/// Collect files of directory recursively, with paths relative to root (separated by `/`), sorted
+fn list_files(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) -> Result<(), Error> {
+    let io_error = |source| Error::Io {
+        file: Some(dir.display().to_string()),
+        source,
+    };
+    let mut entries = fs::read_dir(dir)
+        .map_err(io_error)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(io_error)?;
+    entries.sort();
+
+    for path in entries {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let filename = format!("{}{}", prefix, name);
+        if path.is_dir() {
+            list_files(&path, &format!("{}/", filename), files)?;
+        } else {
+            files.push((filename, path));
+        }
+    }
+    Ok(())
+}
+
+fn create_dir(path: &Path) -> Result<(), Error> {
+    fs::create_dir_all(path).map_err(|source| Error::Io {
+        file: Some(path.display().to_string()),
+        source,
+    })
+}
+
+fn write_dir_file(path: &Path, data: &[u8]) -> Result<(), Error> {
+    fs::write(path, data).map_err(|source| Error::Io {
+        file: Some(path.display().to_string()),
+        source,
+    })
+}
+
+/// Write single uncompressed file to zip archive
+pub(crate) fn write_file<W: Write + Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    filename: &str,
+    data: &[u8],
+) -> Result<(), Error> {
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+
+    zip.start_file(filename, options)
+        .map_err(|source| Error::Zip {
+            file: Some(filename.to_string()),
+            source,
+        })?;
+    zip.write_all(data).map_err(|source| Error::Io {
+        file: Some(filename.to_string()),
+        source,
+    })
 }
 
 #[cfg(test)]
+#[allow(clippy::get_first)]
 mod tests {
     use std::io::Read;
 
-    use crate::pass::{PassBuilder, PassConfig};
+    use crate::{
+        pass::{fields, PassBuilder, PassConfig},
+        personalization::PersonalizationField,
+    };
 
     use super::*;
 
+    /// Valid icon.png, 29 x 29 pixels
+    const ICON: &[u8] = include_bytes!("package/testdata/icon.png");
+
     #[test]
     fn make_package() {
         let pass = PassBuilder::new(PassConfig {
@@ -277,10 +807,571 @@ mod tests {
         let read_json = package_read.pass.make_json().unwrap();
         assert_eq!(expected_json, read_json);
 
+        // Check manifest.json
+        let manifest = package_read.manifest.unwrap();
+        assert_eq!(3, manifest.len());
+        assert!(manifest.verify_item("pass.json", expected_json.as_bytes()));
+        assert!(manifest.verify_item("icon.png", &data));
+        assert!(manifest.verify_item("logo@3x.png", &data));
+
         // Check assets
         println!("{:?}", package.resources);
         assert_eq!(2, package.resources.len());
         assert_eq!("icon.png", package.resources.get(0).unwrap().filename());
         assert_eq!("logo@3x.png", package.resources.get(1).unwrap().filename());
     }
+
+    #[test]
+    fn read_corrupted_package() {
+        let data = [0xFFu8; 1024];
+        let reader = std::io::Cursor::new(&data[..]);
+
+        let err = Package::read(reader).err().unwrap();
+        assert!(matches!(err, Error::Zip { .. }));
+    }
+
+    #[test]
+    fn read_package_without_pass_json() {
+        // Make zip with single resource
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        write_file(&mut zip, "icon.png", &[0u8; 32]).unwrap();
+        let buf = zip.finish().unwrap().into_inner();
+
+        let reader = std::io::Cursor::new(buf);
+        let err = Package::read(reader).err().unwrap();
+        assert!(matches!(err, Error::MissingPassJson));
+    }
+
+    #[test]
+    fn read_package_with_invalid_pass_json() {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        write_file(&mut zip, "pass.json", b"{ not a json").unwrap();
+        let buf = zip.finish().unwrap().into_inner();
+
+        let reader = std::io::Cursor::new(buf);
+        let err = Package::read(reader).err().unwrap();
+        assert!(matches!(err, Error::Json { ref file, .. } if file == "pass.json"));
+    }
+
+    #[test]
+    fn read_package_with_invalid_files() {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: "Apple inc.".into(),
+            description: "Example pass".into(),
+            pass_type_identifier: "com.example.pass".into(),
+            team_identifier: "AA00AA0A0A".into(),
+            serial_number: "ABCDEFG1234567890".into(),
+        })
+        .build();
+
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        write_file(&mut zip, "pass.json", pass.make_json().unwrap().as_bytes()).unwrap();
+        write_file(&mut zip, "icon.png", ICON).unwrap();
+        write_file(&mut zip, "manifest.json", b"{ not a json").unwrap();
+        write_file(&mut zip, "personalization.json", b"{}").unwrap();
+        write_file(&mut zip, "ru.lproj/pass.strings", b"\"title\" = ").unwrap();
+        let buf = zip.finish().unwrap().into_inner();
+
+        // Package is readable, broken files are skipped
+        let package = Package::read(std::io::Cursor::new(&buf)).unwrap();
+        assert!(package.manifest.is_none());
+        assert!(package.personalization.is_none());
+        assert!(package.localizations.is_empty());
+
+        let files: Vec<_> = package
+            .validate()
+            .into_iter()
+            .filter_map(|diagnostic| match diagnostic {
+                Diagnostic::InvalidFile { file, .. } => Some(file),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                "manifest.json",
+                "personalization.json",
+                "ru.lproj/pass.strings"
+            ],
+            files
+        );
+
+        // Verification still fails
+        assert!(Package::verify(std::io::Cursor::new(&buf), WWDR::G4).is_err());
+    }
+
+    /// Make signed package and certificate (PEM) of its issuer
+    fn make_signed_package() -> (Vec<u8>, Vec<u8>) {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: "Apple inc.".into(),
+            description: "Example pass".into(),
+            pass_type_identifier: "com.example.pass".into(),
+            team_identifier: "AA00AA0A0A".into(),
+            serial_number: "ABCDEFG1234567890".into(),
+        })
+        .build();
+
+        let mut package = Package::new(pass);
+        package
+            .add_resource(
+                resource::Type::Icon(resource::Version::Standard),
+                &[0u8; 128][..],
+            )
+            .unwrap();
+
+        // Sign with test certificates
+        let signer = sign::TestSigner::new().unwrap();
+        let wwdr_pem = signer.wwdr_pem().to_vec();
+        package.add_signer(Box::new(signer));
+
+        let mut buf = std::io::Cursor::new(Vec::new());
+        package.write(&mut buf).unwrap();
+
+        (buf.into_inner(), wwdr_pem)
+    }
+
+    /// Copy package files, replacing content of `filename`
+    fn tamper_package(data: &[u8], filename: &str, content: &[u8]) -> Vec<u8> {
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(data)).unwrap();
+        let mut out = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let mut replaced = false;
+
+        for i in 0..zip.len() {
+            let mut file = zip.by_index(i).unwrap();
+            let mut buf = Vec::new();
+            file.read_to_end(&mut buf).unwrap();
+            if file.name() == filename {
+                buf = content.to_vec();
+                replaced = true;
+            }
+            write_file(&mut out, file.name(), &buf).unwrap();
+        }
+        if !replaced {
+            write_file(&mut out, filename, content).unwrap();
+        }
+
+        out.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn verify_signed_package() {
+        let (data, wwdr_pem) = make_signed_package();
+
+        Package::verify(std::io::Cursor::new(&data), WWDR::Custom(&wwdr_pem)).unwrap();
+
+        let package =
+            Package::read_verified(std::io::Cursor::new(&data), WWDR::Custom(&wwdr_pem)).unwrap();
+        assert_eq!("ABCDEFG1234567890", package.pass.config.serial_number);
+        assert_eq!(1, package.resources.len());
+    }
+
+    #[test]
+    fn verify_tampered_package() {
+        let (data, wwdr_pem) = make_signed_package();
+
+        // Modified file
+        let tampered = tamper_package(&data, "icon.png", &[1u8; 128]);
+        let err = Package::verify(std::io::Cursor::new(&tampered), WWDR::Custom(&wwdr_pem))
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::ChecksumMismatch { ref file } if file == "icon.png"));
+
+        // Extra file
+        let tampered = tamper_package(&data, "logo.png", &[1u8; 128]);
+        let err = Package::verify(std::io::Cursor::new(&tampered), WWDR::Custom(&wwdr_pem))
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::UnexpectedFile { ref file } if file == "logo.png"));
+
+        // Modified manifest
+        let tampered = tamper_package(&data, "manifest.json", b"{}");
+        let err = Package::verify(std::io::Cursor::new(&tampered), WWDR::Custom(&wwdr_pem))
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Sign { .. }));
+
+        // Signed by another issuer
+        let err = Package::verify(std::io::Cursor::new(&data), WWDR::G4)
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Sign { .. }));
+    }
+
+    #[test]
+    fn verify_unsigned_package() {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: "Apple inc.".into(),
+            description: "Example pass".into(),
+            pass_type_identifier: "com.example.pass".into(),
+            team_identifier: "AA00AA0A0A".into(),
+            serial_number: "ABCDEFG1234567890".into(),
+        })
+        .build();
+
+        let mut buf = std::io::Cursor::new(Vec::new());
+        Package::new(pass).write(&mut buf).unwrap();
+
+        let err = Package::read_verified(buf, WWDR::G4).err().unwrap();
+        assert!(matches!(err, Error::MissingFile { ref file } if file == "signature"));
+    }
+
+    #[test]
+    fn read_localized_package() {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: "Apple inc.".into(),
+            description: "Example pass".into(),
+            pass_type_identifier: "com.example.pass".into(),
+            team_identifier: "AA00AA0A0A".into(),
+            serial_number: "ABCDEFG1234567890".into(),
+        })
+        .logo_text("logo_text".into())
+        .build();
+
+        let mut package = Package::new(pass);
+        package
+            .add_resource(
+                resource::Type::Logo(resource::Version::Standard),
+                &[0u8; 64][..],
+            )
+            .unwrap();
+
+        // English
+        let mut en = Localization::new();
+        en.add_string("logo_text", "Concert");
+        package.add_localization("en", en);
+
+        // Russian, with localized logo
+        let ru = package.localization_mut("ru");
+        ru.add_string("logo_text", "Концерт");
+        ru.add_resource(
+            resource::Type::Logo(resource::Version::Size2X),
+            &[1u8; 64][..],
+        )
+        .unwrap();
+
+        // Save package as .pkpass
+        let mut buf = std::io::Cursor::new(Vec::new());
+        package.write(&mut buf).unwrap();
+
+        // Read .pkpass
+        let package_read = Package::read(buf).unwrap();
+
+        assert_eq!(1, package_read.resources.len());
+        assert_eq!(2, package_read.localizations.len());
+        assert_eq!(
+            "Concert",
+            package_read.localizations["en"].strings["logo_text"]
+        );
+        let ru = &package_read.localizations["ru"];
+        assert_eq!("Концерт", ru.strings["logo_text"]);
+        assert_eq!(1, ru.resources.len());
+        assert_eq!("logo@2x.png", ru.resources[0].filename());
+        assert_eq!(&[1u8; 64][..], ru.resources[0].as_bytes());
+
+        // Check manifest.json
+        let manifest = package_read.manifest.unwrap();
+        assert!(manifest.get("en.lproj/pass.strings").is_some());
+        assert!(manifest.get("ru.lproj/pass.strings").is_some());
+        assert!(manifest.verify_item("ru.lproj/logo@2x.png", &[1u8; 64]));
+    }
+
+    #[test]
+    fn read_personalized_package() {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: "Apple inc.".into(),
+            description: "Example pass".into(),
+            pass_type_identifier: "com.example.pass".into(),
+            team_identifier: "AA00AA0A0A".into(),
+            serial_number: "ABCDEFG1234567890".into(),
+        })
+        .build();
+
+        let mut package = Package::new(pass);
+        package.personalization = Some(Personalization::new(
+            vec![
+                PersonalizationField::Name,
+                PersonalizationField::EmailAddress,
+            ],
+            "Join rewards program",
+        ));
+        package
+            .add_resource(
+                resource::Type::PersonalizationLogo(resource::Version::Size2X),
+                &[0u8; 64][..],
+            )
+            .unwrap();
+
+        // Save package as .pkpass
+        let mut buf = std::io::Cursor::new(Vec::new());
+        package.write(&mut buf).unwrap();
+
+        // Read .pkpass
+        let package_read = Package::read(buf).unwrap();
+        assert_eq!(package.personalization, package_read.personalization);
+        assert_eq!(
+            resource::Type::PersonalizationLogo(resource::Version::Size2X),
+            package_read.resources[0].get_type()
+        );
+
+        // Check manifest.json
+        let manifest = package_read.manifest.unwrap();
+        let json = package.personalization.unwrap().make_json().unwrap();
+        assert!(manifest.verify_item("personalization.json", json.as_bytes()));
+        assert!(manifest.verify_item("personalizationLogo@2x.png", &[0u8; 64]));
+    }
+
+    #[test]
+    fn write_and_read_dir() {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: "Apple inc.".into(),
+            description: "Example pass".into(),
+            pass_type_identifier: "com.example.pass".into(),
+            team_identifier: "AA00AA0A0A".into(),
+            serial_number: "ABCDEFG1234567890".into(),
+        })
+        .logo_text("logo_text".into())
+        .build();
+        let expected_json = pass.make_json().unwrap();
+
+        let mut package = Package::new(pass);
+        package
+            .add_resource(
+                resource::Type::Icon(resource::Version::Standard),
+                &[0u8; 64][..],
+            )
+            .unwrap();
+        let ru = package.localization_mut("ru");
+        ru.add_string("logo_text", "Концерт");
+        ru.add_resource(
+            resource::Type::Logo(resource::Version::Size2X),
+            &[1u8; 64][..],
+        )
+        .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("passes-dir-{}", std::process::id()));
+        package.write_dir(&dir).unwrap();
+
+        // Unknown files are reported
+        fs::write(dir.join(".DS_Store"), b"").unwrap();
+        fs::write(dir.join("ru.lproj").join("notes.txt"), b"").unwrap();
+
+        let (package_read, unknown_files) = Package::from_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(vec![".DS_Store", "ru.lproj/notes.txt"], unknown_files);
+        assert_eq!(expected_json, package_read.pass.make_json().unwrap());
+        assert_eq!(1, package_read.resources.len());
+        assert_eq!("icon.png", package_read.resources[0].filename());
+        assert_eq!(&[0u8; 64][..], package_read.resources[0].as_bytes());
+        let ru = &package_read.localizations["ru"];
+        assert_eq!("Концерт", ru.strings["logo_text"]);
+        assert_eq!("logo@2x.png", ru.resources[0].filename());
+        assert_eq!(&[1u8; 64][..], ru.resources[0].as_bytes());
+
+        // Round-trip .pkpass -> directory -> .pkpass keeps every file
+        let mut buf = std::io::Cursor::new(Vec::new());
+        package.write(&mut buf).unwrap();
+        let package = Package::read(std::io::Cursor::new(buf.get_ref())).unwrap();
+        package.write_dir(&dir).unwrap();
+        let (mut package_read, unknown_files) = Package::from_dir(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(unknown_files.is_empty());
+
+        let mut buf_read = std::io::Cursor::new(Vec::new());
+        package_read.write(&mut buf_read).unwrap();
+        let package_read = Package::read(buf_read).unwrap();
+        assert_eq!(
+            package.manifest.unwrap().make_json().unwrap(),
+            package_read.manifest.unwrap().make_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn read_dir_without_pass_json() {
+        let dir = std::env::temp_dir().join(format!("passes-empty-dir-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("icon.png"), [0u8; 32]).unwrap();
+
+        let err = Package::from_dir(&dir).err().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(err, Error::MissingPassJson));
+    }
+
+    #[test]
+    fn validate_package() {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: "Apple inc.".into(),
+            description: "Example pass".into(),
+            pass_type_identifier: "com.example.pass".into(),
+            team_identifier: "AA00AA0A0A".into(),
+            serial_number: "ABCDEFG1234567890".into(),
+        })
+        .build();
+
+        let mut package = Package::new(pass);
+        assert_eq!(vec![Diagnostic::MissingIcon], package.validate());
+
+        // Localized icon
+        package
+            .localization_mut("en")
+            .add_resource(resource::Type::Icon(resource::Version::Standard), ICON)
+            .unwrap();
+        assert!(package.validate().is_empty());
+
+        // Icon is missed in one of localizations
+        package.localization_mut("ru").add_string("title", "Билет");
+        assert_eq!(vec![Diagnostic::MissingIcon], package.validate());
+
+        package
+            .add_resource(resource::Type::Icon(resource::Version::Standard), ICON)
+            .unwrap();
+        assert!(package.validate().is_empty());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn validate_package_image_size() {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: "Apple inc.".into(),
+            description: "Example pass".into(),
+            pass_type_identifier: "com.example.pass".into(),
+            team_identifier: "AA00AA0A0A".into(),
+            serial_number: "ABCDEFG1234567890".into(),
+        })
+        .build();
+
+        let make_image = |width, height| {
+            let mut data = Vec::new();
+            image::RgbImage::new(width, height)
+                .write_to(
+                    &mut std::io::Cursor::new(&mut data),
+                    image::ImageFormat::Jpeg,
+                )
+                .unwrap();
+            data
+        };
+
+        let mut package = Package::new(pass);
+        let warnings = package
+            .add_image(
+                resource::Type::Icon(resource::Version::Standard),
+                &make_image(29, 29)[..],
+            )
+            .unwrap();
+        assert!(warnings.is_empty());
+        assert!(package.validate().is_empty());
+
+        // Logo is too wide for allotted space
+        let logo = make_image(400, 50);
+        let warnings = package
+            .add_image(resource::Type::Logo(resource::Version::Standard), &logo[..])
+            .unwrap();
+        let expected = Diagnostic::ImageTooLarge {
+            file: String::from("logo.png"),
+            size: (400, 50),
+            allotted: (160, 50),
+        };
+        assert_eq!(vec![expected.clone()], warnings);
+
+        // Localized images are checked too
+        let logo = package.resources[1].clone();
+        package.localization_mut("ru").resources.push(logo);
+        assert_eq!(vec![expected.clone(), expected.clone()], package.validate());
+
+        // Images, that are not PNG or can't be decoded, are rejected by Wallet
+        package
+            .localization_mut("ru")
+            .resources
+            .push(Resource::from_data(
+                resource::Type::Icon(resource::Version::Standard),
+                make_image(29, 29),
+            ));
+        package.resources.push(Resource::from_data(
+            resource::Type::Icon(resource::Version::Size2X),
+            ICON[..32].to_vec(),
+        ));
+        let invalid: Vec<_> = package
+            .validate()
+            .into_iter()
+            .filter(|diagnostic| matches!(diagnostic, Diagnostic::InvalidFile { .. }))
+            .collect();
+        assert_eq!(2, invalid.len());
+        assert!(invalid[0].is_error());
+        assert!(invalid[0].to_string().starts_with("invalid icon@2x.png: "));
+        assert_eq!(
+            Diagnostic::InvalidFile {
+                file: String::from("ru.lproj/icon.png"),
+                message: String::from("not a PNG image"),
+            },
+            invalid[1]
+        );
+
+        // Icon at lower resolution
+        package.localizations.clear();
+        package.resources.pop();
+        package
+            .add_image(
+                resource::Type::Icon(resource::Version::Size2X),
+                &make_image(29, 29)[..],
+            )
+            .unwrap();
+        assert_eq!(
+            vec![
+                expected,
+                Diagnostic::ImageTooSmall {
+                    file: String::from("icon@2x.png"),
+                    size: (29, 29),
+                    expected: (58, 58),
+                },
+            ],
+            package.validate()
+        );
+    }
+
+    #[test]
+    fn validate_package_hidden_fields() {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: "Apple inc.".into(),
+            description: "Example pass".into(),
+            pass_type_identifier: "com.example.pass".into(),
+            team_identifier: "AA00AA0A0A".into(),
+            serial_number: "ABCDEFG1234567890".into(),
+        })
+        .fields(
+            fields::Type::EventTicket {
+                pass_fields: Default::default(),
+            }
+            .add_secondary_field(fields::Content::new("date", "", Default::default()))
+            .add_secondary_field(fields::Content::new("time", "", Default::default()))
+            .add_secondary_field(fields::Content::new("place", "", Default::default()))
+            .add_auxiliary_field(fields::Content::new("row", "", Default::default()))
+            .add_auxiliary_field(fields::Content::new("seat", "", Default::default())),
+        )
+        .build();
+
+        let mut package = Package::new(pass);
+        package
+            .add_resource(resource::Type::Icon(resource::Version::Standard), ICON)
+            .unwrap();
+        assert!(package.validate().is_empty());
+
+        // Strip image leaves single row for secondary and auxiliary fields
+        package
+            .localization_mut("en")
+            .add_resource(resource::Type::Strip(resource::Version::Size2X), ICON)
+            .unwrap();
+        assert_eq!(
+            ImageSet {
+                strip: true,
+                ..Default::default()
+            },
+            package.image_set()
+        );
+        assert_eq!(
+            vec![Diagnostic::HiddenField {
+                group: fields::FieldGroup::Auxiliary,
+                key: String::from("seat"),
+            }],
+            package.validate()
+        );
+    }
 }
@@ -0,0 +1,194 @@
+use std::io::{Cursor, Read, Seek, Write};
+
+use crate::Error;
+
+use super::{
+    sign::{SignConfig, Signer},
+    write_file, Package,
+};
+
+/// Bundle of several passes, saved as `.pkpasses` file.
+///
+/// `.pkpasses` is a zip archive with signed `.pkpass` files, Wallet adds all of them at once
+/// (e.g. boarding passes for a group). Serve it with `application/vnd.apple.pkpasses` MIME type.
+///
+/// ```
+/// use passes::{bundle::PassBundle, sign::TestSigner, Package, PassBuilder, PassConfig};
+///
+/// let mut bundle = PassBundle::new();
+/// for serial_number in ["001", "002"] {
+///     let pass = PassBuilder::new(PassConfig {
+///         organization_name: String::from("Apple inc."),
+///         description: String::from("Example pass"),
+///         pass_type_identifier: String::from("com.example.pass"),
+///         team_identifier: String::from("AA00AA0A0A"),
+///         serial_number: String::from(serial_number),
+///     })
+///     .build();
+///     bundle.add_package(Package::new(pass));
+/// }
+///
+/// // Every package, that has no own signer, is signed by bundle signer
+/// bundle.add_signer(Box::new(TestSigner::new().unwrap()));
+///
+/// let mut buf = std::io::Cursor::new(Vec::new());
+/// bundle.write(&mut buf).unwrap();
+///
+/// buf.set_position(0);
+/// let bundle = PassBundle::read(buf).unwrap();
+/// assert_eq!(2, bundle.packages.len());
+/// ```
+#[derive(Default)]
+pub struct PassBundle {
+    /// Packages in bundle
+    pub packages: Vec<Package>,
+
+    /// Signer for packages without own signer
+    pub signer: Option<Box<dyn Signer>>,
+}
+
+impl PassBundle {
+    /// Create empty bundle
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add package to bundle
+    pub fn add_package(&mut self, package: Package) {
+        self.packages.push(package);
+    }
+
+    /// Add certificates for signing packages
+    pub fn add_certificates(&mut self, config: SignConfig) {
+        self.signer = Some(Box::new(config));
+    }
+
+    /// Add custom signer for packages, e.g. backed by HSM or KMS
+    pub fn add_signer(&mut self, signer: Box<dyn Signer>) {
+        self.signer = Some(signer);
+    }
+
+    /// Read bundle (.pkpasses), every `.pkpass` file in it is read by [Package::read].
+    ///
+    /// Other files are skipped.
+    pub fn read<R: Read + Seek>(reader: R) -> Result<Self, Error> {
+        let mut zip =
+            zip::ZipArchive::new(reader).map_err(|source| Error::Zip { file: None, source })?;
+
+        let mut packages = Vec::new();
+        for i in 0..zip.len() {
+            let mut file = zip
+                .by_index(i)
+                .map_err(|source| Error::Zip { file: None, source })?;
+            let filename = file.name().to_string();
+            if !filename.ends_with(".pkpass") {
+                continue;
+            }
+
+            let mut buf = Vec::new();
+            file.read_to_end(&mut buf).map_err(|source| Error::Io {
+                file: Some(filename),
+                source,
+            })?;
+            packages.push(Package::read(Cursor::new(buf))?);
+        }
+
+        Ok(Self {
+            packages,
+            signer: None,
+        })
+    }
+
+    /// Write bundle (.pkpasses).
+    ///
+    /// Every package must be signed, by own signer or by bundle signer.
+    pub fn write<W: Write + Seek>(&self, writer: W) -> Result<(), Error> {
+        let mut zip = zip::ZipWriter::new(writer);
+
+        for (i, package) in self.packages.iter().enumerate() {
+            let signer = package
+                .signer
+                .as_deref()
+                .or(self.signer.as_deref())
+                .ok_or_else(|| {
+                    Error::Validation(format!(
+                        "pass {} in bundle has no signer",
+                        package.pass.config.serial_number
+                    ))
+                })?;
+
+            let mut buf = Cursor::new(Vec::new());
+            package.write_signed(&mut buf, Some(signer))?;
+            write_file(&mut zip, &format!("pass{}.pkpass", i + 1), buf.get_ref())?;
+        }
+
+        zip.finish()
+            .map_err(|source| Error::Zip { file: None, source })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        pass::{PassBuilder, PassConfig},
+        sign::{TestSigner, WWDR},
+    };
+
+    use super::*;
+
+    fn make_package(serial_number: &str) -> Package {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: "Apple inc.".into(),
+            description: "Example pass".into(),
+            pass_type_identifier: "com.example.pass".into(),
+            team_identifier: "AA00AA0A0A".into(),
+            serial_number: serial_number.into(),
+        })
+        .build();
+        Package::new(pass)
+    }
+
+    #[test]
+    fn write_and_read_bundle() {
+        let signer = TestSigner::new().unwrap();
+        let wwdr_pem = signer.wwdr_pem().to_vec();
+
+        let mut bundle = PassBundle::new();
+        bundle.add_package(make_package("001"));
+        bundle.add_package(make_package("002"));
+        bundle.add_signer(Box::new(signer));
+
+        let mut buf = Cursor::new(Vec::new());
+        bundle.write(&mut buf).unwrap();
+        let data = buf.into_inner();
+
+        // Every inner package is signed
+        let mut zip = zip::ZipArchive::new(Cursor::new(&data)).unwrap();
+        assert_eq!(2, zip.len());
+        for i in 0..zip.len() {
+            let mut file = zip.by_index(i).unwrap();
+            let mut package = Vec::new();
+            file.read_to_end(&mut package).unwrap();
+            Package::verify(Cursor::new(package), WWDR::Custom(&wwdr_pem)).unwrap();
+        }
+
+        let bundle = PassBundle::read(Cursor::new(&data)).unwrap();
+        let serial_numbers: Vec<_> = bundle
+            .packages
+            .iter()
+            .map(|package| package.pass.config.serial_number.as_str())
+            .collect();
+        assert_eq!(vec!["001", "002"], serial_numbers);
+    }
+
+    #[test]
+    fn write_unsigned_bundle() {
+        let mut bundle = PassBundle::new();
+        bundle.add_package(make_package("001"));
+
+        let err = bundle.write(Cursor::new(Vec::new())).err().unwrap();
+        assert!(matches!(err, Error::Validation(_)));
+    }
+}
@@ -0,0 +1,377 @@
+use std::{collections::BTreeMap, io::Read};
+
+use crate::Error;
+
+use super::resource::{self, Resource};
+
+/// Localized content for one language, saved in `<language>.lproj` folder of .pkpass package.
+///
+/// Contains translations for pass.json strings (pass.strings) and localized images.
+///
+/// ```
+/// use passes::{localization::Localization, resource};
+///
+/// let mut localization = Localization::new();
+/// localization.add_string("event_title", "Концерт");
+/// localization
+///     .add_resource(resource::Type::Logo(resource::Version::Standard), &[0u8; 16][..])
+///     .unwrap();
+/// ```
+///
+/// <https://developer.apple.com/documentation/walletpasses/creating_the_source_for_a_pass#3736718>
+#[derive(Debug, Default, Clone)]
+pub struct Localization {
+    /// Translations (pass.strings), key is a string from pass.json, value is a translated string
+    pub strings: BTreeMap<String, String>,
+
+    /// Localized resources (image files)
+    pub resources: Vec<Resource>,
+}
+
+impl Localization {
+    /// Create empty localization
+    pub fn new() -> Self {
+        Self {
+            strings: BTreeMap::new(),
+            resources: vec![],
+        }
+    }
+
+    /// Add translation for string from pass.json
+    pub fn add_string(&mut self, key: &str, value: &str) {
+        self.strings.insert(String::from(key), String::from(value));
+    }
+
+    /// Adding localized image file.
+    ///
+    /// Reading file to internal buffer storage.
+    pub fn add_resource<R: Read>(
+        &mut self,
+        image_type: resource::Type,
+        mut reader: R,
+    ) -> Result<(), Error> {
+        let mut resource = Resource::new(image_type);
+        std::io::copy(&mut reader, &mut resource).map_err(|source| Error::Io {
+            file: Some(resource.filename()),
+            source,
+        })?;
+        self.resources.push(resource);
+        Ok(())
+    }
+
+    /// Build pass.strings file (UTF-16 with BOM)
+    pub fn make_strings(&self) -> Vec<u8> {
+        make_strings(&self.strings)
+    }
+}
+
+/// Folder name for language, e.g. `en.lproj`
+pub(crate) fn folder_name(language: &str) -> String {
+    format!("{}.lproj", language)
+}
+
+/// Split path inside package to language and file name, e.g. `en.lproj/logo.png` to `en` and `logo.png`
+pub(crate) fn split_path(path: &str) -> Option<(&str, &str)> {
+    let (folder, filename) = path.split_once('/')?;
+    let language = folder.strip_suffix(".lproj")?;
+    if language.is_empty() || filename.is_empty() || filename.contains('/') {
+        return None;
+    }
+    Some((language, filename))
+}
+
+/// Build .strings file from key/value pairs.
+///
+/// Output is encoded in UTF-16 (little-endian) with BOM, as recommended by Apple.
+pub fn make_strings(strings: &BTreeMap<String, String>) -> Vec<u8> {
+    let mut text = String::new();
+    for (key, value) in strings {
+        text.push('"');
+        escape_string(key, &mut text);
+        text.push_str("\" = \"");
+        escape_string(value, &mut text);
+        text.push_str("\";\n");
+    }
+
+    let mut data = vec![0xFF, 0xFE];
+    for unit in text.encode_utf16() {
+        data.extend_from_slice(&unit.to_le_bytes());
+    }
+    data
+}
+
+/// Parse .strings file to key/value pairs.
+///
+/// Supports UTF-16 (with BOM) and UTF-8 encodings, `/* */` and `//` comments.
+pub fn parse_strings(data: &[u8]) -> Result<BTreeMap<String, String>, Error> {
+    parse_strings_file("pass.strings", data)
+}
+
+/// Parse .strings file, errors are reported with `file` path
+pub(crate) fn parse_strings_file(
+    file: &str,
+    data: &[u8],
+) -> Result<BTreeMap<String, String>, Error> {
+    let text = decode_strings(data).map_err(|message| strings_error(file, message))?;
+    StringsParser {
+        file,
+        chars: text.chars().collect(),
+        pos: 0,
+    }
+    .parse()
+}
+
+/// Decode .strings file by BOM
+fn decode_strings(data: &[u8]) -> Result<String, &'static str> {
+    let utf16 = |data: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        if !data.len().is_multiple_of(2) {
+            return Err("odd length of UTF-16 data");
+        }
+        let units: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16(&units).map_err(|_| "invalid UTF-16 data")
+    };
+
+    match data {
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => {
+            String::from_utf8(rest.to_vec()).map_err(|_| "invalid UTF-8 data")
+        }
+        _ => String::from_utf8(data.to_vec()).map_err(|_| "invalid UTF-8 data"),
+    }
+}
+
+/// Escape string for .strings file
+fn escape_string(s: &str, out: &mut String) {
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+}
+
+fn strings_error(file: &str, message: &str) -> Error {
+    Error::Parse {
+        file: String::from(file),
+        message: String::from(message),
+    }
+}
+
+/// Parser for `"key" = "value";` pairs
+struct StringsParser<'a> {
+    file: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl StringsParser<'_> {
+    fn parse(mut self) -> Result<BTreeMap<String, String>, Error> {
+        let mut strings = BTreeMap::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek().is_none() {
+                return Ok(strings);
+            }
+            let key = self.token()?;
+            self.skip_whitespace()?;
+            // Short form `"key";` means key = value
+            let value = if self.peek() == Some(';') {
+                key.clone()
+            } else {
+                self.expect('=')?;
+                self.skip_whitespace()?;
+                self.token()?
+            };
+            self.skip_whitespace()?;
+            self.expect(';')?;
+            strings.insert(key, value);
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        let line = self.chars[..self.pos]
+            .iter()
+            .filter(|&&c| c == '\n')
+            .count()
+            + 1;
+        strings_error(self.file, &format!("{} at line {}", message, line))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            _ => Err(self.error(&format!("expected `{}`", expected))),
+        }
+    }
+
+    /// Skip whitespaces and comments
+    fn skip_whitespace(&mut self) -> Result<(), Error> {
+        loop {
+            match (self.peek(), self.chars.get(self.pos + 1)) {
+                (Some(c), _) if c.is_whitespace() => self.pos += 1,
+                (Some('/'), Some('/')) => while !matches!(self.next(), Some('\n') | None) {},
+                (Some('/'), Some('*')) => {
+                    self.pos += 2;
+                    loop {
+                        match self.next() {
+                            Some('*') if self.peek() == Some('/') => {
+                                self.pos += 1;
+                                break;
+                            }
+                            Some(_) => {}
+                            None => return Err(self.error("unterminated comment")),
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Quoted or unquoted string
+    fn token(&mut self) -> Result<String, Error> {
+        if self.peek() == Some('"') {
+            self.pos += 1;
+            return self.quoted();
+        }
+
+        let mut s = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || "_.-$:/".contains(c) {
+                s.push(c);
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        if s.is_empty() {
+            Err(self.error("expected string"))
+        } else {
+            Ok(s)
+        }
+    }
+
+    /// Quoted string with escapes, opening quote is consumed
+    fn quoted(&mut self) -> Result<String, Error> {
+        let mut s = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.next() {
+                    Some('n') => s.push('\n'),
+                    Some('r') => s.push('\r'),
+                    Some('t') => s.push('\t'),
+                    Some('0') => s.push('\0'),
+                    Some('u') | Some('U') => {
+                        let unit = self.hex_unit()?;
+                        // Surrogate pair is written as two escapes
+                        let c = if (0xD800..0xDC00).contains(&unit) {
+                            if self.next() != Some('\\')
+                                || !matches!(self.next(), Some('u') | Some('U'))
+                            {
+                                return Err(self.error("invalid surrogate pair"));
+                            }
+                            let low = self.hex_unit()?;
+                            char::decode_utf16([unit, low]).next().and_then(|r| r.ok())
+                        } else {
+                            char::from_u32(unit as u32)
+                        };
+                        s.push(c.ok_or_else(|| self.error("invalid unicode escape"))?);
+                    }
+                    Some(c) => s.push(c),
+                    None => return Err(self.error("unterminated string")),
+                },
+                Some(c) => s.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    /// Four hex digits of unicode escape
+    fn hex_unit(&mut self) -> Result<u16, Error> {
+        let digits: String = (0..4).filter_map(|_| self.next()).collect();
+        u16::from_str_radix(&digits, 16).map_err(|_| self.error("invalid unicode escape"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn make_and_parse_strings() {
+        let mut localization = Localization::new();
+        localization.add_string("title", "Билет");
+        localization.add_string("quote", "Say \"hi\"\\n");
+        localization.add_string("multiline", "Line 1\nLine 2");
+
+        let data = localization.make_strings();
+
+        // UTF-16 LE with BOM
+        assert_eq!(&[0xFF, 0xFE, b'"', 0x00], &data[..4]);
+
+        let strings = parse_strings(&data).unwrap();
+        assert_eq!(localization.strings, strings);
+    }
+
+    #[test]
+    fn parse_strings_utf8() {
+        let data = r#"/* Pass title */
+"title" = "Концерт";
+// Unicode escapes
+"emoji" = "\U0001é \UD83D\UDE00";
+simple_key = "simple value"  ;
+"same";
+"#;
+
+        let strings = parse_strings(data.as_bytes()).unwrap();
+
+        assert_eq!(4, strings.len());
+        assert_eq!("Концерт", strings["title"]);
+        assert_eq!("\u{1}é 😀", strings["emoji"]);
+        assert_eq!("simple value", strings["simple_key"]);
+        assert_eq!("same", strings["same"]);
+    }
+
+    #[test]
+    fn parse_strings_error() {
+        let err = parse_strings(b"\"title\" = \"value\"\n\"next\" = ;")
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            "invalid pass.strings: expected `;` at line 2",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn split_localized_path() {
+        assert_eq!(Some(("en", "logo.png")), split_path("en.lproj/logo.png"));
+        assert_eq!(
+            Some(("zh-Hans", "pass.strings")),
+            split_path("zh-Hans.lproj/pass.strings")
+        );
+        assert_eq!(None, split_path("logo.png"));
+        assert_eq!(None, split_path("en/logo.png"));
+        assert_eq!(None, split_path("en.lproj/images/logo.png"));
+    }
+}
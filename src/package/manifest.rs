@@ -1,60 +1,177 @@
-use openssl::sha::Sha1;
-use serde::{ser::SerializeMap, Serialize};
+use std::fmt;
 
-/// Represents manifest.json file, contains SHA-256 of all .pkpass files.
-///
-/// Only serialization supported!
+use openssl::sha::{Sha1, Sha256};
+use serde::{
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Serialize,
+};
+
+use crate::Error;
+
+/// Represents manifest.json file, contains SHA-1 of all .pkpass files (SHA-256 for .order files).
 ///
 /// <https://developer.apple.com/documentation/walletorders/building_a_distributable_order_package>
+#[derive(Debug, Clone, PartialEq)]
 pub struct Manifest {
-    /// All manifest files with SHA-256
+    /// All manifest files with checksum
     items: Vec<Item>,
+
+    /// Hash algorithm of checksums
+    algorithm: Algorithm,
+}
+
+/// Hash algorithm of manifest checksums
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// SHA-1, used by passes
+    Sha1,
+
+    /// SHA-256, used by orders
+    Sha256,
+}
+
+impl Algorithm {
+    /// Calculate hex-encoded checksum of data
+    fn checksum(&self, data: &[u8]) -> String {
+        match self {
+            Algorithm::Sha1 => {
+                let mut hasher = Sha1::new();
+                hasher.update(data);
+                hex::encode(hasher.finish())
+            }
+            Algorithm::Sha256 => {
+                let mut hasher = Sha256::new();
+                hasher.update(data);
+                hex::encode(hasher.finish())
+            }
+        }
+    }
 }
 
 impl Manifest {
     /// Create empty manifest
     pub fn new() -> Self {
-        Self { items: vec![] }
+        Self::with_algorithm(Algorithm::Sha1)
+    }
+
+    /// Create empty manifest with checksums by `algorithm`
+    pub fn with_algorithm(algorithm: Algorithm) -> Self {
+        Self {
+            items: vec![],
+            algorithm,
+        }
+    }
+
+    /// Hash algorithm of checksums.
+    ///
+    /// For manifest from json it is detected by length of checksums.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
     }
 
-    /// Add items & calculate SHA-256
+    /// Add items & calculate checksum
     pub fn add_item(&mut self, path: &str, data: &[u8]) {
-        let mut hasher = Sha1::new();
-        hasher.update(data);
-        let checksum = hasher.finish();
         let item = Item {
             path: path.to_string(),
-            checksum: hex::encode(&checksum),
+            checksum: self.algorithm.checksum(data),
         };
         self.items.push(item);
     }
 
-    /// Add multiple items & calculate SHA-256
+    /// Add multiple items & calculate checksum
     pub fn add_items(&mut self, items: Vec<(&str, &[u8])>) {
         for (path, data) in items.iter() {
             self.add_item(path, data);
         }
     }
 
+    /// Get checksum (hex-encoded) of file by path
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.items
+            .iter()
+            .find(|item| item.path == path)
+            .map(|item| item.checksum.as_str())
+    }
+
+    /// Check that file is listed in manifest with checksum of `data`
+    pub fn verify_item(&self, path: &str, data: &[u8]) -> bool {
+        self.get(path)
+            .is_some_and(|expected| expected.eq_ignore_ascii_case(&self.algorithm.checksum(data)))
+    }
+
+    /// Iterate over all manifest items in order of manifest.json
+    pub fn iter(&self) -> std::slice::Iter<'_, Item> {
+        self.items.iter()
+    }
+
+    /// Number of items in manifest
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if manifest has no items
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
     /// Build JSON output for manifest (manifest.json)
     pub fn make_json(&self) -> Result<String, serde_json::Error> {
         let json = serde_json::to_string(&self)?;
         Ok(json)
     }
 
+    /// Build manifest from json data (manifest.json)
+    ///
+    /// ```
+    /// use passes::manifest::Manifest;
+    ///
+    /// let json = r#"{"pass.json":"2aae6c35c94fcfb415dbe95f408b9ce91ee846ed"}"#;
+    ///
+    /// let manifest = Manifest::from_json(json).unwrap();
+    /// for item in manifest.iter() {
+    ///     println!("{}: {}", item.path, item.checksum);
+    /// }
+    /// assert_eq!(json, manifest.make_json().unwrap());
+    /// ```
+    pub fn from_json(data: &str) -> Result<Self, Error> {
+        let manifest: Manifest = serde_json::from_str(data).map_err(|source| Error::Json {
+            file: String::from("manifest.json"),
+            source,
+        })?;
+        Ok(manifest)
+    }
+
     /// Remove all items from Manifest
     pub fn clear(&mut self) {
         self.items.clear();
     }
 }
 
+impl Default for Manifest {
+    /// Creates an empty `Manifest`.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> IntoIterator for &'a Manifest {
+    type Item = &'a Item;
+    type IntoIter = std::slice::Iter<'a, Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 /// Manifest item
-struct Item {
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
     /// Path of zip file
-    path: String,
+    pub path: String,
 
-    /// SHA-256 hash
-    checksum: String,
+    /// SHA-1 or SHA-256 hash (hex-encoded)
+    pub checksum: String,
 }
 
 impl Serialize for Manifest {
@@ -70,7 +187,44 @@ impl Serialize for Manifest {
     }
 }
 
+impl<'de> Deserialize<'de> for Manifest {
+    /// Deserialize `Manifest`, keeping order of items
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct ManifestVisitor;
+
+        impl<'de> Visitor<'de> for ManifestVisitor {
+            type Value = Manifest;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of file paths to checksums")
+            }
+
+            fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut items = Vec::with_capacity(access.size_hint().unwrap_or(0));
+                while let Some((path, checksum)) = access.next_entry::<String, String>()? {
+                    items.push(Item { path, checksum });
+                }
+                // SHA-256 is 64 hex digits, SHA-1 is 40
+                let algorithm = match items.first() {
+                    Some(item) if item.checksum.len() == 64 => Algorithm::Sha256,
+                    _ => Algorithm::Sha1,
+                };
+                Ok(Manifest { items, algorithm })
+            }
+        }
+
+        deserializer.deserialize_map(ManifestVisitor)
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::vec_init_then_push)]
 mod tests {
     use super::*;
 
@@ -103,4 +257,46 @@ mod tests {
 
         assert_eq!(json_expected, json);
     }
+
+    #[test]
+    fn read_manifest() {
+        let json = r#"{"pass.json":"2aae6c35c94fcfb415dbe95f408b9ce91ee846ed","logo.png":"e2507820ce1bd6d09669504e6a5536f7a3ccc94b","background.png":"05cc11980f5826d11c5c1292a4cd04ad11ddbf45"}"#;
+
+        let manifest = Manifest::from_json(json).unwrap();
+
+        // Order of items is preserved
+        let paths: Vec<&str> = manifest.iter().map(|item| item.path.as_str()).collect();
+        assert_eq!(vec!["pass.json", "logo.png", "background.png"], paths);
+        assert_eq!(
+            Some("e2507820ce1bd6d09669504e6a5536f7a3ccc94b"),
+            manifest.get("logo.png")
+        );
+        assert!(manifest.verify_item("pass.json", "hello world".as_bytes()));
+        assert!(!manifest.verify_item("pass.json", "hello".as_bytes()));
+        assert!(!manifest.verify_item("icon.png", "hello world".as_bytes()));
+
+        // Round-trip
+        assert_eq!(json, manifest.make_json().unwrap());
+    }
+
+    #[test]
+    fn make_sha256_manifest() {
+        let mut manifest = Manifest::with_algorithm(Algorithm::Sha256);
+        manifest.add_item("order.json", "hello world".as_bytes());
+
+        let json = manifest.make_json().unwrap();
+        let json_expected =
+            r#"{"order.json":"b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"}"#;
+        assert_eq!(json_expected, json);
+
+        let manifest = Manifest::from_json(&json).unwrap();
+        assert_eq!(Algorithm::Sha256, manifest.algorithm());
+        assert!(manifest.verify_item("order.json", "hello world".as_bytes()));
+    }
+
+    #[test]
+    fn read_invalid_manifest() {
+        let err = Manifest::from_json(r#"{"pass.json": 1}"#).err().unwrap();
+        assert!(matches!(err, Error::Json { ref file, .. } if file == "manifest.json"));
+    }
 }
@@ -0,0 +1,218 @@
+//! Pass personalization (rewards enrollment), saved as personalization.json of .pkpass package.
+//!
+//! Personalizable pass (store card with [NFC](crate::nfc)) asks user to sign up for a rewards program,
+//! entered info is sent to `personalize` endpoint of web service as [PersonalizationRequest].
+//! Server responds with signed [personalization token](PersonalizationRequest::sign_token)
+//! and then pushes personalized pass to device.
+//!
+//! <https://developer.apple.com/documentation/walletpasses/personalizing_a_pass>
+use serde::{Deserialize, Serialize};
+
+use crate::{sign::Signer, Error};
+
+/// Represents personalization.json file, describes info requested from user.
+///
+/// ```
+/// use passes::personalization::{Personalization, PersonalizationField};
+///
+/// let personalization = Personalization::new(
+///     vec![PersonalizationField::Name, PersonalizationField::EmailAddress],
+///     "Join rewards program",
+/// )
+/// .terms_and_conditions("Rewards are not exchangeable for cash");
+///
+/// let json = personalization.make_json().unwrap();
+/// assert_eq!(personalization, Personalization::from_json(&json).unwrap());
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Personalization {
+    /// Info, that user must enter for signing up
+    pub required_personalization_fields: Vec<PersonalizationField>,
+
+    /// Description of rewards program, displayed on signup sheet
+    pub description: String,
+
+    /// Terms and conditions of rewards program, displayed before signup
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terms_and_conditions: Option<String>,
+}
+
+impl Personalization {
+    /// Create personalization with required fields and description
+    pub fn new(fields: Vec<PersonalizationField>, description: &str) -> Self {
+        Self {
+            required_personalization_fields: fields,
+            description: String::from(description),
+            terms_and_conditions: None,
+        }
+    }
+
+    /// Adding [terms_and_conditions](Personalization::terms_and_conditions)
+    pub fn terms_and_conditions(mut self, terms: &str) -> Self {
+        self.terms_and_conditions = Some(String::from(terms));
+        self
+    }
+
+    /// Build JSON output for personalization (personalization.json)
+    pub fn make_json(&self) -> Result<String, serde_json::Error> {
+        let json = serde_json::to_string_pretty(&self)?;
+        Ok(json)
+    }
+
+    /// Build personalization from json data (personalization.json)
+    pub fn from_json(data: &str) -> Result<Self, Error> {
+        let personalization: Personalization =
+            serde_json::from_str(data).map_err(|source| Error::Json {
+                file: String::from("personalization.json"),
+                source,
+            })?;
+        Ok(personalization)
+    }
+}
+
+/// Info, that user enters for personalization
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PersonalizationField {
+    /// Full name (given and family name)
+    #[serde(rename = "PKPassPersonalizationFieldName")]
+    Name,
+
+    /// Postal code (and ISO country code)
+    #[serde(rename = "PKPassPersonalizationFieldPostalCode")]
+    PostalCode,
+
+    /// Email address
+    #[serde(rename = "PKPassPersonalizationFieldEmailAddress")]
+    EmailAddress,
+
+    /// Phone number
+    #[serde(rename = "PKPassPersonalizationFieldPhoneNumber")]
+    PhoneNumber,
+}
+
+/// Body of personalize request, sent by device to web service
+/// (`POST /v1/passes/{passTypeIdentifier}/{serialNumber}/personalize`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalizationRequest {
+    /// Token, that server signs and returns in response
+    pub personalization_token: String,
+
+    /// Info, entered by user
+    pub required_personalization_info: PersonalizationInfo,
+}
+
+impl PersonalizationRequest {
+    /// Sign [personalization_token](PersonalizationRequest::personalization_token), result is a body of response.
+    ///
+    /// Token is signed by pass certificate, same as manifest.json (detached PKCS#7 signature).
+    pub fn sign_token(&self, signer: &dyn Signer) -> Result<Vec<u8>, Error> {
+        signer.sign(self.personalization_token.as_bytes())
+    }
+}
+
+/// Info, entered by user for [required_personalization_fields](Personalization::required_personalization_fields)
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalizationInfo {
+    /// Email address
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_address: Option<String>,
+
+    /// Family name
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+
+    /// Full name
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+
+    /// Given name
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+
+    /// ISO country code of postal code
+    #[serde(rename = "ISOCountryCode")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iso_country_code: Option<String>,
+
+    /// Phone number
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone_number: Option<String>,
+
+    /// Postal code
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sign::{self, TestSigner};
+
+    use super::*;
+
+    #[test]
+    fn make_personalization() {
+        let personalization = Personalization::new(
+            vec![
+                PersonalizationField::Name,
+                PersonalizationField::PostalCode,
+                PersonalizationField::EmailAddress,
+                PersonalizationField::PhoneNumber,
+            ],
+            "Join rewards program",
+        )
+        .terms_and_conditions("Terms");
+
+        let json_expected = r#"{
+  "requiredPersonalizationFields": [
+    "PKPassPersonalizationFieldName",
+    "PKPassPersonalizationFieldPostalCode",
+    "PKPassPersonalizationFieldEmailAddress",
+    "PKPassPersonalizationFieldPhoneNumber"
+  ],
+  "description": "Join rewards program",
+  "termsAndConditions": "Terms"
+}"#;
+        assert_eq!(json_expected, personalization.make_json().unwrap());
+        assert_eq!(
+            personalization,
+            Personalization::from_json(json_expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn sign_personalization_token() {
+        let json = r#"{
+            "personalizationToken": "0123456789abcdef",
+            "requiredPersonalizationInfo": {
+                "emailAddress": "john@example.com",
+                "fullName": "John Appleseed",
+                "ISOCountryCode": "US",
+                "postalCode": "95014"
+            }
+        }"#;
+        let request: PersonalizationRequest = serde_json::from_str(json).unwrap();
+        let info = &request.required_personalization_info;
+        assert_eq!(Some("US"), info.iso_country_code.as_deref());
+        assert_eq!(None, info.phone_number);
+
+        let signer = TestSigner::new().unwrap();
+        let signature = request.sign_token(&signer).unwrap();
+        sign::verify(
+            sign::WWDR::Custom(signer.wwdr_pem()),
+            b"0123456789abcdef",
+            &signature,
+        )
+        .unwrap();
+    }
+}
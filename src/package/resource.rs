@@ -1,18 +1,27 @@
 use std::{
+    fmt,
     io::{Read, Write},
     str::FromStr,
+    sync::Arc,
 };
 
 use regex::Regex;
 
-/// Represents image file, saved in .pkpass package
-#[derive(Debug)]
+use crate::fields;
+
+#[cfg(feature = "image")]
+mod convert;
+
+/// Represents image file, saved in .pkpass package.
+///
+/// File buffer is shared between clones, so cloning resource doesn't copy image data.
+#[derive(Debug, Clone)]
 pub struct Resource {
     /// Type of image (represents file name)
     image_type: Type,
 
     /// File buffer
-    buffer: Vec<u8>,
+    buffer: Arc<Vec<u8>>,
 }
 
 impl Resource {
@@ -20,7 +29,15 @@ impl Resource {
     pub fn new(image_type: Type) -> Self {
         Self {
             image_type,
-            buffer: vec![],
+            buffer: Arc::new(vec![]),
+        }
+    }
+
+    /// Create resource with data
+    pub(crate) fn from_data(image_type: Type, buffer: Vec<u8>) -> Self {
+        Self {
+            image_type,
+            buffer: Arc::new(buffer),
         }
     }
 
@@ -43,11 +60,11 @@ impl Resource {
 // Reading resource data
 impl Write for Resource {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.buffer.write(buf)
+        Arc::make_mut(&mut self.buffer).write(buf)
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
-        self.buffer.flush()
+        Ok(())
     }
 }
 
@@ -66,13 +83,24 @@ pub enum Version {
     Size3X,
 }
 
+impl Version {
+    /// Pixels per point, e.g. 2 for @2x
+    pub fn scale(&self) -> u32 {
+        match self {
+            Version::Standard => 1,
+            Version::Size2X => 2,
+            Version::Size3X => 3,
+        }
+    }
+}
+
 // To String
-impl ToString for Version {
-    fn to_string(&self) -> String {
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Version::Standard => "".into(),
-            Version::Size2X => "@2x".into(),
-            Version::Size3X => "@3x".into(),
+            Version::Standard => write!(f, ""),
+            Version::Size2X => write!(f, "@2x"),
+            Version::Size3X => write!(f, "@3x"),
         }
     }
 }
@@ -101,8 +129,13 @@ impl FromStr for Version {
 /// * On iPhone 6 and 6 Plus The allotted space is 375 x 98 points for event tickets, 375 x 144 points for gift cards and coupons, and 375 x 123 in all other cases.
 /// * On prior hardware The allotted space is 320 x 84 points for event tickets, 320 x 110 points for other pass styles with a square barcode on devices with 3.5 inch screens, and 320 x 123 in all other cases.
 /// * The thumbnail image (thumbnail.png) displayed next to the fields on the front of the pass. The allotted space is 90 x 90 points. The aspect ratio should be in the range of 2:3 to 3:2, otherwise the image is cropped.
+/// * The artwork image (artwork.png) is displayed as the background of a poster event ticket (iOS 18).
+/// * The secondary logo image (secondaryLogo.png) is displayed on a poster event ticket next to the event details (iOS 18).
+/// * The personalization logo (personalizationLogo.png) is displayed on the rewards enrollment sheet of [personalizable](crate::personalization) pass. The allotted space is 150 x 40 points.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Type {
+    /// The artwork image of poster event ticket (artwork.png)
+    Artwork(Version),
     /// The background image (background.png)
     Background(Version),
     /// The footer image (footer.png)
@@ -111,21 +144,88 @@ pub enum Type {
     Icon(Version),
     /// The logo image (logo.png)
     Logo(Version),
+    /// The logo image of rewards enrollment sheet (personalizationLogo.png)
+    PersonalizationLogo(Version),
+    /// The secondary logo image of poster event ticket (secondaryLogo.png)
+    SecondaryLogo(Version),
     /// The strip image (strip.png)
     Strip(Version),
     /// The thumbnail image (thumbnail.png)
     Thumbnail(Version),
 }
 
-impl ToString for Type {
-    fn to_string(&self) -> String {
+impl Type {
+    /// Get image size version
+    pub fn version(&self) -> Version {
+        match self {
+            Type::Artwork(v)
+            | Type::Background(v)
+            | Type::Footer(v)
+            | Type::Icon(v)
+            | Type::Logo(v)
+            | Type::PersonalizationLogo(v)
+            | Type::SecondaryLogo(v)
+            | Type::Strip(v)
+            | Type::Thumbnail(v) => v.clone(),
+        }
+    }
+
+    /// Same type of image with other size version
+    pub fn with_version(&self, version: Version) -> Type {
         match self {
-            Type::Background(v) => format!("background{}.png", v.to_string()),
-            Type::Footer(v) => format!("footer{}.png", v.to_string()),
-            Type::Icon(v) => format!("icon{}.png", v.to_string()),
-            Type::Logo(v) => format!("logo{}.png", v.to_string()),
-            Type::Strip(v) => format!("strip{}.png", v.to_string()),
-            Type::Thumbnail(v) => format!("thumbnail{}.png", v.to_string()),
+            Type::Artwork(_) => Type::Artwork(version),
+            Type::Background(_) => Type::Background(version),
+            Type::Footer(_) => Type::Footer(version),
+            Type::Icon(_) => Type::Icon(version),
+            Type::Logo(_) => Type::Logo(version),
+            Type::PersonalizationLogo(_) => Type::PersonalizationLogo(version),
+            Type::SecondaryLogo(_) => Type::SecondaryLogo(version),
+            Type::Strip(_) => Type::Strip(version),
+            Type::Thumbnail(_) => Type::Thumbnail(version),
+        }
+    }
+
+    /// Allotted space (width, height) in points for pass `style`, `None` if Apple doesn't document it.
+    ///
+    /// Only strip image depends on pass style: 375 x 98 points for event tickets, 375 x 144 for
+    /// store cards and coupons, 375 x 123 for other styles.
+    pub fn allotted_points(&self, style: &fields::Type) -> Option<(u32, u32)> {
+        match self {
+            Type::Background(_) => Some((180, 220)),
+            Type::Footer(_) => Some((286, 15)),
+            Type::Icon(_) => Some((29, 29)),
+            Type::Logo(_) => Some((160, 50)),
+            Type::PersonalizationLogo(_) => Some((150, 40)),
+            Type::Strip(_) => match style {
+                fields::Type::EventTicket { .. } => Some((375, 98)),
+                fields::Type::StoreCard { .. } | fields::Type::Coupon { .. } => Some((375, 144)),
+                _ => Some((375, 123)),
+            },
+            Type::Thumbnail(_) => Some((90, 90)),
+            Type::Artwork(_) | Type::SecondaryLogo(_) => None,
+        }
+    }
+
+    /// Allotted space (width, height) in pixels for size version of image and pass `style`
+    pub fn allotted_pixels(&self, style: &fields::Type) -> Option<(u32, u32)> {
+        let scale = self.version().scale();
+        self.allotted_points(style)
+            .map(|(width, height)| (width * scale, height * scale))
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Artwork(v) => write!(f, "artwork{}.png", v),
+            Type::Background(v) => write!(f, "background{}.png", v),
+            Type::Footer(v) => write!(f, "footer{}.png", v),
+            Type::Icon(v) => write!(f, "icon{}.png", v),
+            Type::Logo(v) => write!(f, "logo{}.png", v),
+            Type::PersonalizationLogo(v) => write!(f, "personalizationLogo{}.png", v),
+            Type::SecondaryLogo(v) => write!(f, "secondaryLogo{}.png", v),
+            Type::Strip(v) => write!(f, "strip{}.png", v),
+            Type::Thumbnail(v) => write!(f, "thumbnail{}.png", v),
         }
     }
 }
@@ -135,8 +235,8 @@ impl FromStr for Type {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // Note: format field unused
-        let re = Regex::new(r"(?P<type>\w+)(?P<version>@\dx)?\.(?P<format>png)").unwrap();
-        let captures = re.captures(&s);
+        let re = Regex::new(r"^(?P<type>\w+)(?P<version>@\dx)?\.(?P<format>png)$").unwrap();
+        let captures = re.captures(s);
 
         // Extract captures
         if let Some(captures) = captures {
@@ -150,19 +250,18 @@ impl FromStr for Type {
                 Version::Standard
             };
 
-            if let Ok(image_type_str) = captures["type"].parse::<String>() {
-                // Match type & version
-                match image_type_str.as_str() {
-                    "background" => Ok(Type::Background(version)),
-                    "footer" => Ok(Type::Footer(version)),
-                    "icon" => Ok(Type::Icon(version)),
-                    "logo" => Ok(Type::Logo(version)),
-                    "strip" => Ok(Type::Strip(version)),
-                    "thumbnail" => Ok(Type::Thumbnail(version)),
-                    _ => Err(()),
-                }
-            } else {
-                Err(())
+            // Match type & version
+            match &captures["type"] {
+                "artwork" => Ok(Type::Artwork(version)),
+                "background" => Ok(Type::Background(version)),
+                "footer" => Ok(Type::Footer(version)),
+                "icon" => Ok(Type::Icon(version)),
+                "logo" => Ok(Type::Logo(version)),
+                "personalizationLogo" => Ok(Type::PersonalizationLogo(version)),
+                "secondaryLogo" => Ok(Type::SecondaryLogo(version)),
+                "strip" => Ok(Type::Strip(version)),
+                "thumbnail" => Ok(Type::Thumbnail(version)),
+                _ => Err(()),
             }
         } else {
             Err(())
@@ -171,6 +270,7 @@ impl FromStr for Type {
 }
 
 #[cfg(test)]
+#[allow(clippy::unnecessary_mut_passed, clippy::unused_io_amount)]
 mod tests {
     use super::*;
 
@@ -193,6 +293,12 @@ mod tests {
 
         let t = Type::Logo(Version::Size2X);
         assert_eq!("logo@2x.png", t.to_string());
+
+        let t = Type::SecondaryLogo(Version::Size3X);
+        assert_eq!("secondaryLogo@3x.png", t.to_string());
+
+        let t = Type::PersonalizationLogo(Version::Size2X);
+        assert_eq!("personalizationLogo@2x.png", t.to_string());
     }
 
     #[test]
@@ -202,5 +308,52 @@ mod tests {
 
         let t = Type::from_str("logo@2x.png").unwrap();
         assert_eq!(Type::Logo(Version::Size2X), t);
+
+        let t = Type::from_str("artwork@2x.png").unwrap();
+        assert_eq!(Type::Artwork(Version::Size2X), t);
+
+        let t = Type::from_str("secondaryLogo.png").unwrap();
+        assert_eq!(Type::SecondaryLogo(Version::Standard), t);
+
+        let t = Type::from_str("personalizationLogo@3x.png").unwrap();
+        assert_eq!(Type::PersonalizationLogo(Version::Size3X), t);
+    }
+
+    #[test]
+    fn check_allotted_space() {
+        let generic = fields::Type::Generic {
+            pass_fields: Default::default(),
+        };
+        let t = Type::Logo(Version::Size3X);
+        assert_eq!(Version::Size3X, t.version());
+        assert_eq!(Some((160, 50)), t.allotted_points(&generic));
+        assert_eq!(Some((480, 150)), t.allotted_pixels(&generic));
+
+        let t = t.with_version(Version::Standard);
+        assert_eq!(Type::Logo(Version::Standard), t);
+        assert_eq!(Some((160, 50)), t.allotted_pixels(&generic));
+
+        assert_eq!(
+            None,
+            Type::Artwork(Version::Size2X).allotted_pixels(&generic)
+        );
+
+        // Strip depends on pass style
+        let t = Type::Strip(Version::Size2X);
+        assert_eq!(Some((750, 246)), t.allotted_pixels(&generic));
+        let event_ticket = fields::Type::EventTicket {
+            pass_fields: Default::default(),
+        };
+        assert_eq!(Some((750, 196)), t.allotted_pixels(&event_ticket));
+        let coupon = fields::Type::Coupon {
+            pass_fields: Default::default(),
+        };
+        assert_eq!(Some((750, 288)), t.allotted_pixels(&coupon));
+    }
+
+    #[test]
+    fn check_type_from_localized_path() {
+        assert!(Type::from_str("en.lproj/logo.png").is_err());
+        assert!(Type::from_str("logo.png.bak").is_err());
     }
 }
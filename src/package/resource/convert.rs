@@ -0,0 +1,307 @@
+use std::io::Cursor;
+
+use image::{imageops::FilterType, DynamicImage, ImageFormat, ImageReader};
+
+use crate::{fields, pass::validation::Diagnostic, Error};
+
+use super::{Resource, Type, Version};
+
+impl Resource {
+    /// Decode PNG or JPEG image and re-encode it to PNG.
+    ///
+    /// Requires `image` feature. Returns warning if image is larger than [allotted space](Type::allotted_pixels)
+    /// for pass `style`.
+    ///
+    /// ```
+    /// use passes::{fields, resource::{Resource, Type, Version}};
+    ///
+    /// # let mut data = Vec::new();
+    /// # image::RgbImage::new(160, 50)
+    /// #     .write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::Jpeg)
+    /// #     .unwrap();
+    /// let style = fields::Type::Generic {
+    ///     pass_fields: Default::default(),
+    /// };
+    /// let (resource, warnings) =
+    ///     Resource::from_image(Type::Logo(Version::Standard), &data, &style).unwrap();
+    /// assert_eq!(b"\x89PNG", &resource.as_bytes()[..4]);
+    /// assert!(warnings.is_empty());
+    /// ```
+    pub fn from_image(
+        image_type: Type,
+        data: &[u8],
+        style: &fields::Type,
+    ) -> Result<(Self, Vec<Diagnostic>), Error> {
+        let image = decode(&image_type, data)?;
+        let data = encode(&image_type, &image)?;
+        let resource = Resource::from_data(image_type, data);
+        let warnings = resource.check_size(style)?.into_iter().collect();
+        Ok((resource, warnings))
+    }
+
+    /// Make standard, @2x and @3x images from one high-resolution PNG or JPEG image.
+    ///
+    /// Requires `image` feature. Source image is used as @3x version (version of `image_type` is ignored),
+    /// smaller versions are downscaled from it. Returns warnings for images larger than
+    /// [allotted space](Type::allotted_pixels) for pass `style`.
+    pub fn from_image_set(
+        image_type: Type,
+        data: &[u8],
+        style: &fields::Type,
+    ) -> Result<(Vec<Self>, Vec<Diagnostic>), Error> {
+        let image = decode(&image_type, data)?;
+        let (width, height) = (image.width(), image.height());
+
+        let mut resources = Vec::new();
+        let mut warnings = Vec::new();
+        for version in [Version::Standard, Version::Size2X, Version::Size3X] {
+            let scale = version.scale();
+            let image_type = image_type.with_version(version);
+            let data = if scale == 3 {
+                encode(&image_type, &image)?
+            } else {
+                let width = ((width * scale + 1) / 3).max(1);
+                let height = ((height * scale + 1) / 3).max(1);
+                encode(
+                    &image_type,
+                    &image.resize_exact(width, height, FilterType::Lanczos3),
+                )?
+            };
+            let resource = Resource::from_data(image_type, data);
+            warnings.extend(resource.check_size(style)?);
+            resources.push(resource);
+        }
+        Ok((resources, warnings))
+    }
+
+    /// Size of image (width, height) in pixels.
+    ///
+    /// Requires `image` feature. Only image header is decoded.
+    pub fn dimensions(&self) -> Result<(u32, u32), Error> {
+        ImageReader::new(Cursor::new(self.as_bytes()))
+            .with_guessed_format()
+            .map_err(|source| Error::Io {
+                file: Some(self.filename()),
+                source,
+            })?
+            .into_dimensions()
+            .map_err(|err| Error::Parse {
+                file: self.filename(),
+                message: err.to_string(),
+            })
+    }
+
+    /// Check image size against [allotted space](Type::allotted_pixels) of its type for pass `style`.
+    ///
+    /// Requires `image` feature. Reports images larger than allotted space, and icon or thumbnail
+    /// smaller than it (icon must fill it, thumbnail must fill it on its longer side).
+    /// Returns [Error::Parse], if image is not PNG or can't be decoded.
+    pub fn check_size(&self, style: &fields::Type) -> Result<Option<Diagnostic>, Error> {
+        if !self.as_bytes().starts_with(PNG_SIGNATURE) {
+            return Err(Error::Parse {
+                file: self.filename(),
+                message: String::from("not a PNG image"),
+            });
+        }
+        let size = self.dimensions()?;
+        let Some(allotted) = self.image_type.allotted_pixels(style) else {
+            return Ok(None);
+        };
+
+        if size.0 > allotted.0 || size.1 > allotted.1 {
+            return Ok(Some(Diagnostic::ImageTooLarge {
+                file: self.filename(),
+                size,
+                allotted,
+            }));
+        }
+        let too_small = match self.image_type {
+            Type::Icon(_) => size.0 < allotted.0 || size.1 < allotted.1,
+            Type::Thumbnail(_) => size.0.max(size.1) < allotted.0.max(allotted.1),
+            _ => false,
+        };
+        if too_small {
+            return Ok(Some(Diagnostic::ImageTooSmall {
+                file: self.filename(),
+                size,
+                expected: allotted,
+            }));
+        }
+        Ok(None)
+    }
+}
+
+/// Decode PNG or JPEG image
+fn decode(image_type: &Type, data: &[u8]) -> Result<DynamicImage, Error> {
+    image::load_from_memory(data).map_err(|err| Error::Parse {
+        file: image_type.to_string(),
+        message: err.to_string(),
+    })
+}
+
+/// Encode image to PNG
+fn encode(image_type: &Type, image: &DynamicImage) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+        .map_err(|source| Error::Io {
+            file: Some(image_type.to_string()),
+            source: std::io::Error::other(source),
+        })?;
+    Ok(data)
+}
+
+/// First bytes of every PNG file
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[cfg(test)]
+mod tests {
+    use image::RgbImage;
+
+    use super::*;
+
+    fn generic() -> fields::Type {
+        fields::Type::Generic {
+            pass_fields: Default::default(),
+        }
+    }
+
+    fn make_image(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+        let mut data = Vec::new();
+        RgbImage::new(width, height)
+            .write_to(&mut Cursor::new(&mut data), format)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn convert_jpeg_to_png() {
+        let data = make_image(90, 90, ImageFormat::Jpeg);
+        let (resource, warnings) =
+            Resource::from_image(Type::Thumbnail(Version::Standard), &data, &generic()).unwrap();
+
+        assert_eq!(b"\x89PNG", &resource.as_bytes()[..4]);
+        assert_eq!((90, 90), resource.dimensions().unwrap());
+        assert!(warnings.is_empty());
+
+        let err = Resource::from_image(Type::Icon(Version::Standard), b"not an image", &generic())
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Parse { ref file, .. } if file == "icon.png"));
+    }
+
+    #[test]
+    fn make_image_set() {
+        let data = make_image(480, 150, ImageFormat::Png);
+        let (resources, warnings) =
+            Resource::from_image_set(Type::Logo(Version::Standard), &data, &generic()).unwrap();
+
+        let sizes: Vec<_> = resources
+            .iter()
+            .map(|resource| (resource.filename(), resource.dimensions().unwrap()))
+            .collect();
+        assert_eq!(
+            vec![
+                (String::from("logo.png"), (160, 50)),
+                (String::from("logo@2x.png"), (320, 100)),
+                (String::from("logo@3x.png"), (480, 150)),
+            ],
+            sizes
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn warn_about_large_image() {
+        let data = make_image(1200, 300, ImageFormat::Png);
+        let (_, warnings) =
+            Resource::from_image_set(Type::Strip(Version::Standard), &data, &generic()).unwrap();
+
+        assert_eq!(3, warnings.len());
+        assert_eq!(
+            Diagnostic::ImageTooLarge {
+                file: String::from("strip.png"),
+                size: (400, 100),
+                allotted: (375, 123),
+            },
+            warnings[0]
+        );
+        assert!(!warnings[0].is_error());
+
+        // Strip of 375 x 120 points fits store card, but not event ticket
+        let data = make_image(1125, 360, ImageFormat::Png);
+        let store_card = fields::Type::StoreCard {
+            pass_fields: Default::default(),
+        };
+        let (_, warnings) =
+            Resource::from_image_set(Type::Strip(Version::Standard), &data, &store_card).unwrap();
+        assert!(warnings.is_empty());
+
+        let event_ticket = fields::Type::EventTicket {
+            pass_fields: Default::default(),
+        };
+        let (resources, warnings) =
+            Resource::from_image_set(Type::Strip(Version::Standard), &data, &event_ticket).unwrap();
+        assert_eq!(3, warnings.len());
+        assert_eq!(
+            Some(Diagnostic::ImageTooLarge {
+                file: String::from("strip@3x.png"),
+                size: (1125, 360),
+                allotted: (1125, 294),
+            }),
+            resources[2].check_size(&event_ticket).unwrap()
+        );
+    }
+
+    #[test]
+    fn warn_about_small_image() {
+        // Icon at 1x resolution in @2x file
+        let data = make_image(29, 29, ImageFormat::Png);
+        let (_, warnings) =
+            Resource::from_image(Type::Icon(Version::Size2X), &data, &generic()).unwrap();
+        assert_eq!(
+            vec![Diagnostic::ImageTooSmall {
+                file: String::from("icon@2x.png"),
+                size: (29, 29),
+                expected: (58, 58),
+            }],
+            warnings
+        );
+        assert!(!warnings[0].is_error());
+
+        // Thumbnail fills allotted space on longer side
+        let data = make_image(180, 270, ImageFormat::Png);
+        let (_, warnings) =
+            Resource::from_image_set(Type::Thumbnail(Version::Standard), &data, &generic())
+                .unwrap();
+        assert!(warnings.is_empty());
+        let data = make_image(90, 90, ImageFormat::Png);
+        let (resource, _) =
+            Resource::from_image(Type::Thumbnail(Version::Size3X), &data, &generic()).unwrap();
+        assert!(matches!(
+            resource.check_size(&generic()).unwrap(),
+            Some(Diagnostic::ImageTooSmall { .. })
+        ));
+
+        // Logo can be narrower than allotted space
+        let data = make_image(100, 50, ImageFormat::Png);
+        let (_, warnings) =
+            Resource::from_image(Type::Logo(Version::Standard), &data, &generic()).unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn check_size_of_invalid_image() {
+        let jpeg = Resource::from_data(
+            Type::Icon(Version::Standard),
+            make_image(29, 29, ImageFormat::Jpeg),
+        );
+        let err = jpeg.check_size(&generic()).err().unwrap();
+        assert_eq!("invalid icon.png: not a PNG image", err.to_string());
+
+        let png = make_image(29, 29, ImageFormat::Png);
+        let truncated = Resource::from_data(Type::Icon(Version::Standard), png[..20].to_vec());
+        let err = truncated.check_size(&generic()).err().unwrap();
+        assert!(matches!(err, Error::Parse { ref file, .. } if file == "icon.png"));
+    }
+}
@@ -1,10 +1,18 @@
+use std::sync::{Arc, OnceLock};
+
 use openssl::{
     error::ErrorStack,
+    pkcs12::{ParsedPkcs12_2, Pkcs12},
+    pkcs7::{Pkcs7, Pkcs7Flags},
     pkey::{PKey, Private},
+    provider::Provider,
     rsa::Rsa,
-    x509::X509,
+    stack::Stack,
+    x509::{store::X509StoreBuilder, verify::X509VerifyFlags, X509PurposeId, X509},
 };
 
+use crate::Error;
+
 /// Configuration for package signing.
 ///
 /// Contains WWDR (Apple Worldwide Developer Relations), Signer Certificate (Developer), Signer Certificate Key (Developer)
@@ -16,18 +24,49 @@ pub struct SignConfig {
 }
 
 impl SignConfig {
-    /// Create new config from buffers
-    pub fn new(wwdr: WWDR, sign_cert: &[u8], sign_key: &[u8]) -> Result<SignConfig, ErrorStack> {
-        let cert;
-        match wwdr {
-            WWDR::G4 => cert = X509::from_der(G4_CERT)?,
-            WWDR::Custom(buf) => cert = X509::from_pem(buf)?,
-        }
+    /// Create new config from buffers.
+    ///
+    /// Signer certificate can be PEM or DER (.cer file from Apple Developer portal).
+    /// Signer key can be PEM or DER, PKCS#1 (RSA), SEC1 (EC) or PKCS#8.
+    pub fn new(wwdr: WWDR, sign_cert: &[u8], sign_key: &[u8]) -> Result<SignConfig, Error> {
+        Self::load(wwdr, sign_cert, sign_key, None)
+    }
 
-        let sign_cert = X509::from_pem(sign_cert)?;
+    /// Create new config from buffers, with encrypted signer key.
+    ///
+    /// Same as [new](SignConfig::new), but signer key is decrypted with `password`.
+    pub fn with_password(
+        wwdr: WWDR,
+        sign_cert: &[u8],
+        sign_key: &[u8],
+        password: &[u8],
+    ) -> Result<SignConfig, Error> {
+        Self::load(wwdr, sign_cert, sign_key, Some(password))
+    }
 
-        let rsa = Rsa::private_key_from_pem(sign_key)?;
-        let sign_key = PKey::from_rsa(rsa)?;
+    /// Create new config from PKCS#12 bundle (.p12 file), exported from Keychain Access.
+    ///
+    /// Bundle must contain signer certificate and signer key. Bundles, encrypted with legacy
+    /// algorithms (RC2, 3DES) by Keychain Access, are decrypted by OpenSSL legacy provider.
+    ///
+    /// ```rust,ignore
+    /// let data = std::fs::read("certs/pass.p12").unwrap();
+    /// let sign_config = sign::SignConfig::from_pkcs12(&data, "password", sign::WWDR::G4).unwrap();
+    /// ```
+    pub fn from_pkcs12(data: &[u8], password: &str, wwdr: WWDR) -> Result<SignConfig, Error> {
+        let cert = wwdr
+            .certificate()
+            .map_err(|source| sign_error("WWDR certificate", source))?;
+
+        let bundle =
+            parse_pkcs12(data, password).map_err(|source| sign_error("PKCS#12 bundle", source))?;
+
+        let sign_cert = bundle.cert.ok_or_else(|| {
+            Error::Validation(String::from("PKCS#12 bundle has no signer certificate"))
+        })?;
+        let sign_key = bundle
+            .pkey
+            .ok_or_else(|| Error::Validation(String::from("PKCS#12 bundle has no signer key")))?;
 
         Ok(SignConfig {
             cert,
@@ -35,6 +74,218 @@ impl SignConfig {
             sign_key,
         })
     }
+
+    /// Load certificates and key from buffers
+    fn load(
+        wwdr: WWDR,
+        sign_cert: &[u8],
+        sign_key: &[u8],
+        password: Option<&[u8]>,
+    ) -> Result<SignConfig, Error> {
+        let cert = wwdr
+            .certificate()
+            .map_err(|source| sign_error("WWDR certificate", source))?;
+
+        let sign_cert = if is_pem(sign_cert) {
+            X509::from_pem(sign_cert)
+        } else {
+            X509::from_der(sign_cert)
+        }
+        .map_err(|source| sign_error("signer certificate", source))?;
+
+        let sign_key = match (is_pem(sign_key), password) {
+            // Empty password callback prevents OpenSSL from prompting in terminal for encrypted key
+            (true, None) => PKey::private_key_from_pem_callback(sign_key, |_| Ok(0)),
+            (false, None) => PKey::private_key_from_der(sign_key),
+            (true, Some(password)) => PKey::private_key_from_pem_passphrase(sign_key, password),
+            (false, Some(password)) => PKey::private_key_from_pkcs8_passphrase(sign_key, password),
+        }
+        .map_err(|source| sign_error("signer key", source))?;
+
+        Ok(SignConfig {
+            cert,
+            sign_cert,
+            sign_key,
+        })
+    }
+}
+
+/// Parse PKCS#12 bundle, retry with legacy provider if algorithm is not supported by default provider
+fn parse_pkcs12(data: &[u8], password: &str) -> Result<ParsedPkcs12_2, ErrorStack> {
+    let parse = || Pkcs12::from_der(data).and_then(|pkcs12| pkcs12.parse2(password));
+    parse().or_else(|err| {
+        if load_legacy_provider() {
+            parse()
+        } else {
+            Err(err)
+        }
+    })
+}
+
+/// Load OpenSSL legacy provider (RC2, 3DES) once, it stays loaded until process exit
+fn load_legacy_provider() -> bool {
+    static LOADED: OnceLock<bool> = OnceLock::new();
+    *LOADED.get_or_init(|| match Provider::try_load(None, "legacy", true) {
+        Ok(provider) => {
+            std::mem::forget(provider);
+            true
+        }
+        Err(_) => false,
+    })
+}
+
+/// Check that buffer is PEM encoded (otherwise DER)
+fn is_pem(data: &[u8]) -> bool {
+    data.trim_ascii_start().starts_with(b"-----BEGIN")
+}
+
+/// Makes signature for package (signature file).
+///
+/// Implement this trait to keep signer key outside of process memory, e.g. in HSM or cloud KMS.
+/// [SignConfig] is the default implementation, which signs with OpenSSL and key in memory.
+pub trait Signer: Send + Sync {
+    /// Make detached PKCS#7 signature (DER) for manifest.json data.
+    ///
+    /// Signature must include signer certificate and WWDR certificate.
+    /// Errors of signer backend (not OpenSSL) are returned as [Error::Signer].
+    fn sign(&self, manifest: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+/// Shared signer, e.g. for signing packages in several threads
+impl<S: Signer + ?Sized> Signer for Arc<S> {
+    fn sign(&self, manifest: &[u8]) -> Result<Vec<u8>, Error> {
+        self.as_ref().sign(manifest)
+    }
+}
+
+impl Signer for SignConfig {
+    fn sign(&self, manifest: &[u8]) -> Result<Vec<u8>, Error> {
+        let sign = || -> Result<Vec<u8>, ErrorStack> {
+            // Add WWDR cert to chain
+            let mut certs = Stack::new()?;
+            certs.push(self.cert.clone())?;
+
+            // Make signature without signing content
+            let pkcs7 = Pkcs7::sign(
+                &self.sign_cert,
+                &self.sign_key,
+                &certs,
+                manifest,
+                Pkcs7Flags::DETACHED,
+            )?;
+
+            pkcs7.to_der()
+        };
+
+        sign().map_err(|source| sign_error("signature", source))
+    }
+}
+
+/// Signer with locally generated certificates, use it for tests only.
+///
+/// Generates self-signed certificate, used as WWDR certificate, and signer certificate issued by it.
+/// Apple devices reject packages signed by `TestSigner`, but they can be verified by
+/// [Package::verify](crate::Package::verify) with custom WWDR from [wwdr_pem](TestSigner::wwdr_pem).
+///
+/// ```
+/// use passes::{sign::{Signer, TestSigner, WWDR}, Package, PassBuilder, PassConfig};
+///
+/// let pass = PassBuilder::new(PassConfig {
+///     organization_name: "Test organization".into(),
+///     description: "Test description for pass".into(),
+///     pass_type_identifier: "com.example.pass".into(),
+///     team_identifier: "AA00AA0A0A".into(),
+///     serial_number: "ABCDEFG1234567890".into(),
+/// })
+/// .build();
+///
+/// let signer = TestSigner::new().unwrap();
+/// let wwdr = signer.wwdr_pem().to_vec();
+///
+/// let mut package = Package::new(pass);
+/// package.add_signer(Box::new(signer));
+///
+/// let mut buf = std::io::Cursor::new(Vec::new());
+/// package.write(&mut buf).unwrap();
+///
+/// Package::verify(buf, WWDR::Custom(&wwdr)).unwrap();
+/// ```
+pub struct TestSigner {
+    config: SignConfig,
+    wwdr_pem: Vec<u8>,
+}
+
+impl TestSigner {
+    /// Generate new certificates and keys
+    pub fn new() -> Result<Self, Error> {
+        let generate = || -> Result<Self, ErrorStack> {
+            let (cert, cert_key) = make_cert()?;
+            let key_pair = PKey::from_rsa(Rsa::generate(2048)?)?;
+            let (sign_cert, sign_key) = make_issued_cert(&cert, &cert_key, key_pair)?;
+            let wwdr_pem = cert.to_pem()?;
+            Ok(Self {
+                config: SignConfig {
+                    cert,
+                    sign_cert,
+                    sign_key,
+                },
+                wwdr_pem,
+            })
+        };
+
+        generate().map_err(|source| Error::Sign { file: None, source })
+    }
+
+    /// Generated WWDR certificate (PEM), use it with [WWDR::Custom]
+    pub fn wwdr_pem(&self) -> &[u8] {
+        &self.wwdr_pem
+    }
+
+    /// Generated certificates and signer key
+    pub fn config(&self) -> &SignConfig {
+        &self.config
+    }
+}
+
+impl Signer for TestSigner {
+    fn sign(&self, manifest: &[u8]) -> Result<Vec<u8>, Error> {
+        self.config.sign(manifest)
+    }
+}
+
+/// Verify detached PKCS#7 signature (DER) for data.
+///
+/// Signer certificate must be issued by WWDR certificate. WWDR certificate is trusted as is,
+/// Apple root certificate is not required.
+pub fn verify(wwdr: WWDR, data: &[u8], signature: &[u8]) -> Result<(), Error> {
+    let cert = wwdr
+        .certificate()
+        .map_err(|source| sign_error("WWDR certificate", source))?;
+
+    let verify_signature = || -> Result<(), ErrorStack> {
+        let pkcs7 = Pkcs7::from_der(signature)?;
+
+        // Trust WWDR as intermediate certificate without full chain to Apple root
+        let mut store = X509StoreBuilder::new()?;
+        store.add_cert(cert)?;
+        store.set_flags(X509VerifyFlags::PARTIAL_CHAIN)?;
+        store.set_purpose(X509PurposeId::ANY)?;
+        let store = store.build();
+
+        // Signer certificate is included in signature
+        let certs = Stack::new()?;
+        pkcs7.verify(&certs, &store, Some(data), None, Pkcs7Flags::BINARY)
+    };
+
+    verify_signature().map_err(|source| sign_error("signature", source))
+}
+
+/// Wrap OpenSSL error with name of certificate or key
+fn sign_error(file: &str, source: ErrorStack) -> Error {
+    Error::Sign {
+        file: Some(file.to_string()),
+        source,
+    }
 }
 
 /// G4 certificate from https://www.apple.com/certificateauthority/
@@ -46,62 +297,113 @@ pub enum WWDR<'a> {
     Custom(&'a [u8]),
 }
 
+impl WWDR<'_> {
+    /// Load WWDR certificate
+    fn certificate(&self) -> Result<X509, ErrorStack> {
+        match self {
+            WWDR::G4 => X509::from_der(G4_CERT),
+            WWDR::Custom(buf) => X509::from_pem(buf),
+        }
+    }
+}
+
+/// Make x509 certificate and private key
+fn make_cert() -> Result<(X509, PKey<Private>), ErrorStack> {
+    let rsa = Rsa::generate(2048)?;
+    let key_pair = PKey::from_rsa(rsa)?;
+
+    let mut x509_name = openssl::x509::X509NameBuilder::new()?;
+    x509_name.append_entry_by_text("C", "RU")?;
+    x509_name.append_entry_by_text("ST", "Primorskii krai")?;
+    x509_name.append_entry_by_text("O", "Some organization")?;
+    x509_name.append_entry_by_text("CN", "CERT TEST")?;
+    let x509_name = x509_name.build();
+
+    let mut cert_builder = X509::builder()?;
+    cert_builder.set_version(2)?;
+    let serial_number = {
+        let mut serial = openssl::bn::BigNum::new()?;
+        serial.rand(159, openssl::bn::MsbOption::MAYBE_ZERO, false)?;
+        serial.to_asn1_integer()?
+    };
+    cert_builder.set_serial_number(&serial_number)?;
+    cert_builder.set_subject_name(&x509_name)?;
+    cert_builder.set_issuer_name(&x509_name)?;
+    cert_builder.set_pubkey(&key_pair)?;
+    let not_before = openssl::asn1::Asn1Time::days_from_now(0)?;
+    cert_builder.set_not_before(&not_before)?;
+    let not_after = openssl::asn1::Asn1Time::days_from_now(365)?;
+    cert_builder.set_not_after(&not_after)?;
+
+    cert_builder.append_extension(
+        openssl::x509::extension::BasicConstraints::new()
+            .critical()
+            .ca()
+            .build()?,
+    )?;
+    cert_builder.append_extension(
+        openssl::x509::extension::KeyUsage::new()
+            .critical()
+            .key_cert_sign()
+            .crl_sign()
+            .build()?,
+    )?;
+
+    let subject_key_identifier = openssl::x509::extension::SubjectKeyIdentifier::new()
+        .build(&cert_builder.x509v3_context(None, None))?;
+    cert_builder.append_extension(subject_key_identifier)?;
+
+    cert_builder.sign(&key_pair, openssl::hash::MessageDigest::sha256())?;
+    let cert = cert_builder.build();
+
+    Ok((cert, key_pair))
+}
+
+/// Make x509 certificate and private key, issued by `issuer` certificate
+fn make_issued_cert(
+    issuer: &X509,
+    issuer_key: &PKey<Private>,
+    key_pair: PKey<Private>,
+) -> Result<(X509, PKey<Private>), ErrorStack> {
+    let mut x509_name = openssl::x509::X509NameBuilder::new()?;
+    x509_name.append_entry_by_text("C", "RU")?;
+    x509_name.append_entry_by_text("O", "Some organization")?;
+    x509_name.append_entry_by_text("CN", "Pass Type ID: com.example.pass")?;
+    let x509_name = x509_name.build();
+
+    let mut cert_builder = X509::builder()?;
+    cert_builder.set_version(2)?;
+    let serial_number = {
+        let mut serial = openssl::bn::BigNum::new()?;
+        serial.rand(159, openssl::bn::MsbOption::MAYBE_ZERO, false)?;
+        serial.to_asn1_integer()?
+    };
+    cert_builder.set_serial_number(&serial_number)?;
+    cert_builder.set_subject_name(&x509_name)?;
+    cert_builder.set_issuer_name(issuer.subject_name())?;
+    cert_builder.set_pubkey(&key_pair)?;
+    let not_before = openssl::asn1::Asn1Time::days_from_now(0)?;
+    cert_builder.set_not_before(&not_before)?;
+    let not_after = openssl::asn1::Asn1Time::days_from_now(365)?;
+    cert_builder.set_not_after(&not_after)?;
+
+    cert_builder.append_extension(
+        openssl::x509::extension::KeyUsage::new()
+            .critical()
+            .digital_signature()
+            .build()?,
+    )?;
+
+    cert_builder.sign(issuer_key, openssl::hash::MessageDigest::sha256())?;
+    let cert = cert_builder.build();
+
+    Ok((cert, key_pair))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    /// Make x509 certificate and private key
-    fn make_cert() -> Result<(X509, PKey<Private>), ErrorStack> {
-        let rsa = Rsa::generate(2048)?;
-        let key_pair = PKey::from_rsa(rsa)?;
-
-        let mut x509_name = openssl::x509::X509NameBuilder::new()?;
-        x509_name.append_entry_by_text("C", "RU")?;
-        x509_name.append_entry_by_text("ST", "Primorskii krai")?;
-        x509_name.append_entry_by_text("O", "Some organization")?;
-        x509_name.append_entry_by_text("CN", "CERT TEST")?;
-        let x509_name = x509_name.build();
-
-        let mut cert_builder = X509::builder()?;
-        cert_builder.set_version(2)?;
-        let serial_number = {
-            let mut serial = openssl::bn::BigNum::new()?;
-            serial.rand(159, openssl::bn::MsbOption::MAYBE_ZERO, false)?;
-            serial.to_asn1_integer()?
-        };
-        cert_builder.set_serial_number(&serial_number)?;
-        cert_builder.set_subject_name(&x509_name)?;
-        cert_builder.set_issuer_name(&x509_name)?;
-        cert_builder.set_pubkey(&key_pair)?;
-        let not_before = openssl::asn1::Asn1Time::days_from_now(0)?;
-        cert_builder.set_not_before(&not_before)?;
-        let not_after = openssl::asn1::Asn1Time::days_from_now(365)?;
-        cert_builder.set_not_after(&not_after)?;
-
-        cert_builder.append_extension(
-            openssl::x509::extension::BasicConstraints::new()
-                .critical()
-                .ca()
-                .build()?,
-        )?;
-        cert_builder.append_extension(
-            openssl::x509::extension::KeyUsage::new()
-                .critical()
-                .key_cert_sign()
-                .crl_sign()
-                .build()?,
-        )?;
-
-        let subject_key_identifier = openssl::x509::extension::SubjectKeyIdentifier::new()
-            .build(&cert_builder.x509v3_context(None, None))?;
-        cert_builder.append_extension(subject_key_identifier)?;
-
-        cert_builder.sign(&key_pair, openssl::hash::MessageDigest::sha256())?;
-        let cert = cert_builder.build();
-
-        Ok((cert, key_pair))
-    }
-
     #[test]
     fn create_config() {
         // Generate certificate
@@ -112,4 +414,172 @@ mod tests {
 
         let _ = SignConfig::new(WWDR::G4, sign_cert, sign_key).unwrap();
     }
+
+    #[test]
+    fn create_config_invalid_key() {
+        let (sign_cert, _) = make_cert().unwrap();
+        let sign_cert = &sign_cert.to_pem().unwrap();
+
+        let err = SignConfig::new(WWDR::G4, sign_cert, b"not a key")
+            .err()
+            .unwrap();
+
+        assert_eq!("signing error in signer key", err.to_string());
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let (wwdr_cert, wwdr_key) = make_cert().unwrap();
+        let key_pair = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let (sign_cert, sign_key) = make_issued_cert(&wwdr_cert, &wwdr_key, key_pair).unwrap();
+
+        let wwdr_pem = wwdr_cert.to_pem().unwrap();
+        let config = SignConfig::new(
+            WWDR::Custom(&wwdr_pem),
+            &sign_cert.to_pem().unwrap(),
+            &sign_key.private_key_to_pem_pkcs8().unwrap(),
+        )
+        .unwrap();
+
+        let data = b"{\"pass.json\":\"2aae6c35c94fcfb415dbe95f408b9ce91ee846ed\"}";
+        let signature = config.sign(data).unwrap();
+
+        // Valid signature
+        verify(WWDR::Custom(&wwdr_pem), data, &signature).unwrap();
+
+        // Modified data
+        let err = verify(WWDR::Custom(&wwdr_pem), b"{}", &signature)
+            .err()
+            .unwrap();
+        assert_eq!("signing error in signature", err.to_string());
+
+        // Certificate is not issued by Apple WWDR
+        assert!(verify(WWDR::G4, data, &signature).is_err());
+    }
+
+    #[test]
+    fn test_signer() {
+        let signer = TestSigner::new().unwrap();
+
+        let data = b"{}";
+        let signature = signer.sign(data).unwrap();
+
+        verify(WWDR::Custom(signer.wwdr_pem()), data, &signature).unwrap();
+    }
+
+    #[test]
+    fn create_config_key_formats() {
+        let (wwdr_cert, wwdr_key) = make_cert().unwrap();
+        let wwdr_pem = wwdr_cert.to_pem().unwrap();
+        let rsa = Rsa::generate(2048).unwrap();
+        let key_pair = PKey::from_rsa(rsa.clone()).unwrap();
+        let (sign_cert, sign_key) = make_issued_cert(&wwdr_cert, &wwdr_key, key_pair).unwrap();
+
+        let cert_pem = sign_cert.to_pem().unwrap();
+        let cert_der = sign_cert.to_der().unwrap();
+
+        // PKCS#1 PEM
+        let key = rsa.private_key_to_pem().unwrap();
+        SignConfig::new(WWDR::Custom(&wwdr_pem), &cert_pem, &key).unwrap();
+
+        // PKCS#8 DER with DER certificate
+        let key = sign_key.private_key_to_pkcs8().unwrap();
+        SignConfig::new(WWDR::Custom(&wwdr_pem), &cert_der, &key).unwrap();
+
+        // Encrypted PKCS#8 PEM
+        let key = sign_key
+            .private_key_to_pem_pkcs8_passphrase(openssl::symm::Cipher::aes_256_cbc(), b"secret")
+            .unwrap();
+        SignConfig::with_password(WWDR::Custom(&wwdr_pem), &cert_pem, &key, b"secret").unwrap();
+        assert!(SignConfig::new(WWDR::Custom(&wwdr_pem), &cert_pem, &key).is_err());
+        assert!(
+            SignConfig::with_password(WWDR::Custom(&wwdr_pem), &cert_pem, &key, b"wrong").is_err()
+        );
+
+        // Encrypted PKCS#1 PEM
+        let key = rsa
+            .private_key_to_pem_passphrase(openssl::symm::Cipher::aes_128_cbc(), b"secret")
+            .unwrap();
+        SignConfig::with_password(WWDR::Custom(&wwdr_pem), &cert_pem, &key, b"secret").unwrap();
+    }
+
+    #[test]
+    fn create_config_ec_key() {
+        let (wwdr_cert, wwdr_key) = make_cert().unwrap();
+        let wwdr_pem = wwdr_cert.to_pem().unwrap();
+        let group =
+            openssl::ec::EcGroup::from_curve_name(openssl::nid::Nid::X9_62_PRIME256V1).unwrap();
+        let ec_key = openssl::ec::EcKey::generate(&group).unwrap();
+        let key_pair = PKey::from_ec_key(ec_key.clone()).unwrap();
+        let (sign_cert, _) = make_issued_cert(&wwdr_cert, &wwdr_key, key_pair).unwrap();
+
+        // SEC1 PEM
+        let config = SignConfig::new(
+            WWDR::Custom(&wwdr_pem),
+            &sign_cert.to_pem().unwrap(),
+            &ec_key.private_key_to_pem().unwrap(),
+        )
+        .unwrap();
+
+        let signature = config.sign(b"{}").unwrap();
+        verify(WWDR::Custom(&wwdr_pem), b"{}", &signature).unwrap();
+    }
+
+    #[test]
+    fn create_config_from_pkcs12() {
+        let (wwdr_cert, wwdr_key) = make_cert().unwrap();
+        let wwdr_pem = wwdr_cert.to_pem().unwrap();
+        let key_pair = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let (sign_cert, sign_key) = make_issued_cert(&wwdr_cert, &wwdr_key, key_pair).unwrap();
+
+        let pkcs12 = Pkcs12::builder()
+            .name("Pass Type ID: com.example.pass")
+            .pkey(&sign_key)
+            .cert(&sign_cert)
+            .build2("secret")
+            .unwrap()
+            .to_der()
+            .unwrap();
+
+        let config = SignConfig::from_pkcs12(&pkcs12, "secret", WWDR::Custom(&wwdr_pem)).unwrap();
+        let signature = config.sign(b"{}").unwrap();
+        verify(WWDR::Custom(&wwdr_pem), b"{}", &signature).unwrap();
+
+        // Wrong password
+        let err = SignConfig::from_pkcs12(&pkcs12, "wrong", WWDR::Custom(&wwdr_pem))
+            .err()
+            .unwrap();
+        assert_eq!("signing error in PKCS#12 bundle", err.to_string());
+    }
+
+    #[test]
+    fn custom_signer_error() {
+        struct KmsSigner;
+
+        impl Signer for KmsSigner {
+            fn sign(&self, _manifest: &[u8]) -> Result<Vec<u8>, Error> {
+                Err(Error::Signer {
+                    source: "key is disabled".into(),
+                })
+            }
+        }
+
+        let signer: Arc<dyn Signer> = Arc::new(KmsSigner);
+        let err = signer.sign(b"{}").err().unwrap();
+        assert_eq!("signer error", err.to_string());
+        assert_eq!(
+            "key is disabled",
+            std::error::Error::source(&err).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn create_config_from_legacy_pkcs12() {
+        // Exported with RC2-40 and 3DES, same as Keychain Access
+        let pkcs12 = include_bytes!("testdata/legacy.p12");
+
+        let config = SignConfig::from_pkcs12(pkcs12, "secret", WWDR::G4).unwrap();
+        let signature = config.sign(b"{}").unwrap();
+        assert!(!signature.is_empty());
+    }
 }
@@ -0,0 +1,316 @@
+//! Templates for mass pass issuance.
+//!
+//! [PassTemplate] is made from one pass design with `{{placeholder}}` in strings of pass.json
+//! (field values and labels, barcode messages, serial number, semantic tags, etc.) and `pass.strings`.
+//! Every render makes a new [Package] for one data record, image data is shared between renders.
+use std::{collections::BTreeMap, sync::Arc};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    sign::{SignConfig, Signer},
+    Error, Package, Pass,
+};
+
+use super::{localization::Localization, personalization::Personalization, resource::Resource};
+
+/// Pass design with placeholders, renders [Package] per data record.
+///
+/// Placeholder is a variable name in double braces, e.g. `{{name}}`. Nested values are
+/// accessed by dot-separated path, e.g. `{{customer.name}}` or `{{seats.0}}`.
+///
+/// ```
+/// use passes::{barcode::Barcode, template::PassTemplate, Package, PassBuilder, PassConfig};
+///
+/// let pass = PassBuilder::new(PassConfig {
+///     organization_name: String::from("Apple inc."),
+///     description: String::from("Example pass"),
+///     pass_type_identifier: String::from("com.example.pass"),
+///     team_identifier: String::from("AA00AA0A0A"),
+///     serial_number: String::from("TICKET-{{id}}"),
+/// })
+/// .add_barcode(Barcode {
+///     message: String::from("{{id}}"),
+///     ..Default::default()
+/// })
+/// .build();
+///
+/// let template = PassTemplate::new(Package::new(pass)).unwrap();
+/// let package = template.render(&serde_json::json!({ "id": 1234 })).unwrap();
+/// assert_eq!("TICKET-1234", package.pass.config.serial_number);
+/// assert_eq!("1234", package.pass.barcodes[0].message);
+///
+/// // Missing variables are errors
+/// assert!(template.render(&serde_json::json!({})).is_err());
+/// ```
+pub struct PassTemplate {
+    /// pass.json with placeholders
+    pass: Value,
+
+    /// Resources (image files), shared with rendered packages
+    resources: Vec<Resource>,
+
+    /// Localizations, `pass.strings` may contain placeholders
+    localizations: BTreeMap<String, Localization>,
+
+    /// Personalization, same for all rendered packages
+    personalization: Option<Personalization>,
+
+    /// Signer for rendered packages
+    signer: Option<Arc<dyn Signer>>,
+}
+
+impl PassTemplate {
+    /// Create template from package, e.g. read by [Package::read].
+    ///
+    /// Signer of package (if any) is used for every rendered package.
+    pub fn new(package: Package) -> Result<Self, Error> {
+        let pass = serde_json::to_value(&package.pass).map_err(|source| Error::Json {
+            file: String::from("pass.json"),
+            source,
+        })?;
+        Ok(Self {
+            pass,
+            resources: package.resources,
+            localizations: package.localizations,
+            personalization: package.personalization,
+            signer: package.signer.map(Arc::from),
+        })
+    }
+
+    /// Add certificates for signing rendered packages
+    pub fn add_certificates(&mut self, config: SignConfig) {
+        self.signer = Some(Arc::new(config));
+    }
+
+    /// Add custom signer for rendered packages, e.g. backed by HSM or KMS
+    pub fn add_signer(&mut self, signer: Box<dyn Signer>) {
+        self.signer = Some(Arc::from(signer));
+    }
+
+    /// Render package for data record, e.g. [serde_json::Value] or any [Serialize] struct.
+    ///
+    /// Returns [Error::MissingVariable] if placeholder is not found in data.
+    pub fn render<T: Serialize + ?Sized>(&self, data: &T) -> Result<Package, Error> {
+        let data = serde_json::to_value(data).map_err(|source| Error::Json {
+            file: String::from("template data"),
+            source,
+        })?;
+
+        let pass = render_value(&self.pass, &data)?;
+        let pass: Pass = serde_json::from_value(pass).map_err(|source| Error::Json {
+            file: String::from("pass.json"),
+            source,
+        })?;
+
+        let mut localizations = self.localizations.clone();
+        for (language, localization) in localizations.iter_mut() {
+            let file = format!("{}.lproj/pass.strings", language);
+            for value in localization.strings.values_mut() {
+                *value = render_str(value, &data, &file)?;
+            }
+        }
+
+        let mut package = Package::new(pass);
+        package.resources = self.resources.clone();
+        package.localizations = localizations;
+        package.personalization = self.personalization.clone();
+        if let Some(signer) = &self.signer {
+            package.add_signer(Box::new(Arc::clone(signer)));
+        }
+        Ok(package)
+    }
+}
+
+/// Replace placeholders in every string of JSON value
+fn render_value(value: &Value, data: &Value) -> Result<Value, Error> {
+    Ok(match value {
+        Value::String(text) => Value::String(render_str(text, data, "pass.json")?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render_value(item, data))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| Ok((key.clone(), render_value(item, data)?)))
+                .collect::<Result<_, Error>>()?,
+        ),
+        _ => value.clone(),
+    })
+}
+
+/// Replace placeholders in string, `file` is used for errors
+fn render_str(text: &str, data: &Value, file: &str) -> Result<String, Error> {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            return Err(Error::Parse {
+                file: String::from(file),
+                message: format!("unclosed placeholder in \"{}\"", text),
+            });
+        };
+        let name = rest[start + 2..start + end].trim();
+        match lookup(data, name)? {
+            Value::String(value) => output.push_str(value),
+            Value::Null => {}
+            value => output.push_str(&value.to_string()),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Find variable in data by dot-separated path
+fn lookup<'a>(data: &'a Value, name: &str) -> Result<&'a Value, Error> {
+    name.split('.')
+        .try_fold(data, |value, key| match value {
+            Value::Object(map) => map.get(key),
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+        .ok_or_else(|| Error::MissingVariable {
+            name: String::from(name),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        fields,
+        pass::{PassBuilder, PassConfig},
+        resource,
+        sign::TestSigner,
+    };
+
+    use super::*;
+
+    fn make_template() -> PassTemplate {
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: "Apple inc.".into(),
+            description: "Example pass".into(),
+            pass_type_identifier: "com.example.pass".into(),
+            team_identifier: "AA00AA0A0A".into(),
+            serial_number: "{{serial}}".into(),
+        })
+        .fields(
+            fields::Type::Generic {
+                pass_fields: Default::default(),
+            }
+            .add_primary_field(fields::Content::new(
+                "name",
+                "{{ customer.name }}",
+                fields::ContentOptions {
+                    label: Some(String::from("{{customer.title}}")),
+                    ..Default::default()
+                },
+            ))
+            .add_secondary_field(fields::Content::new(
+                "seat",
+                "Row {{seats.0.row}}, seat {{seats.0.number}}",
+                Default::default(),
+            )),
+        )
+        .build();
+
+        let mut package = Package::new(pass);
+        package
+            .add_resource(
+                resource::Type::Icon(resource::Version::Standard),
+                &[0u8; 256][..],
+            )
+            .unwrap();
+        package
+            .localization_mut("ru")
+            .add_string("{{customer.title}}", "Гость {{customer.name}}");
+        PassTemplate::new(package).unwrap()
+    }
+
+    #[test]
+    fn render_template() {
+        #[derive(Serialize)]
+        struct Customer {
+            name: &'static str,
+            title: &'static str,
+        }
+
+        let template = make_template();
+        let data = json!({
+            "serial": "A-0001",
+            "customer": Customer { name: "John Appleseed", title: "Guest" },
+            "seats": [{ "row": 12, "number": 7 }],
+        });
+        let package = template.render(&data).unwrap();
+
+        assert_eq!("A-0001", package.pass.config.serial_number);
+        let json: Value = serde_json::from_str(&package.pass.make_json().unwrap()).unwrap();
+        let field = &json["generic"]["primaryFields"][0];
+        assert_eq!("John Appleseed", field["value"]);
+        assert_eq!("Guest", field["label"]);
+        assert_eq!(
+            "Row 12, seat 7",
+            json["generic"]["secondaryFields"][0]["value"]
+        );
+
+        // Only values of pass.strings are rendered
+        assert_eq!(
+            "Гость John Appleseed",
+            package.localizations["ru"].strings["{{customer.title}}"]
+        );
+
+        // Image data is shared with template
+        assert!(std::ptr::eq(
+            template.resources[0].as_bytes(),
+            package.resources[0].as_bytes()
+        ));
+    }
+
+    #[test]
+    fn render_missing_variable() {
+        let template = make_template();
+        let data = json!({
+            "serial": "A-0001",
+            "customer": { "name": "John Appleseed" },
+            "seats": [],
+        });
+
+        let err = template.render(&data).err().unwrap();
+        assert!(matches!(err, Error::MissingVariable { ref name } if name == "customer.title"));
+    }
+
+    #[test]
+    fn render_signed() {
+        let mut template = make_template();
+        template.add_signer(Box::new(TestSigner::new().unwrap()));
+
+        for serial in ["A-0001", "A-0002"] {
+            let data = json!({
+                "serial": serial,
+                "customer": { "name": "John Appleseed", "title": "Guest" },
+                "seats": [{ "row": 1, "number": 1 }],
+            });
+            let mut package = template.render(&data).unwrap();
+            assert!(package.signer.is_some());
+
+            let mut buf = std::io::Cursor::new(Vec::new());
+            package.write(&mut buf).unwrap();
+            let mut zip = zip::ZipArchive::new(buf).unwrap();
+            assert!(zip.by_name("signature").is_ok());
+        }
+    }
+
+    #[test]
+    fn render_unclosed_placeholder() {
+        let err = render_str("Hello {{name", &json!({ "name": "John" }), "pass.json")
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Parse { ref file, .. } if file == "pass.json"));
+    }
+}
@@ -2,21 +2,28 @@ use chrono::{DateTime, Utc};
 use is_empty::IsEmpty;
 use serde::{Deserialize, Serialize};
 
+use crate::Error;
+
 use self::barcode::Barcode;
 use self::beacon::Beacon;
+use self::event_guide::EventGuide;
 use self::location::Location;
 use self::nfc::NFC;
+use self::relevant_date::RelevantDate;
 use self::semantic_tags::SemanticTags;
-use self::visual_appearance::VisualAppearance;
+use self::visual_appearance::{StyleScheme, VisualAppearance};
 use self::web_service::WebService;
 
 pub mod barcode;
 pub mod beacon;
-mod date_format;
+pub(crate) mod date_format;
+pub mod event_guide;
 pub mod fields;
 pub mod location;
 pub mod nfc;
+pub mod relevant_date;
 pub mod semantic_tags;
+pub mod validation;
 pub mod visual_appearance;
 pub mod web_service;
 
@@ -84,17 +91,48 @@ pub struct Pass {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub appearance: Option<VisualAppearance>,
 
+    /// Styles of pass in order of preference, e.g. poster event ticket with fallback to event ticket (iOS 18).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub preferred_style_schemes: Vec<StyleScheme>,
+
+    /// Controls whether to display the header of poster event ticket without darkening gradient (iOS 18).
+    ///
+    /// The default value is false.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "_is_false")]
+    pub suppress_header_darkening: bool,
+
+    /// Controls whether Wallet picks colors of poster event ticket from artwork image (iOS 18).
+    ///
+    /// The default value is false.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "_is_false")]
+    pub use_automatic_colors: bool,
+
     /// The text to display next to the logo on the pass.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub logo_text: Option<String>,
 
+    /// The text to display next to the logo on poster event ticket (iOS 18).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_logo_text: Option<String>,
+
     /// The date and time when the pass becomes relevant
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "date_format")]
     pub relevant_date: Option<DateTime<Utc>>,
 
+    /// Dates and date intervals when the pass is relevant (iOS 18).
+    ///
+    /// Wallet uses it instead of [relevant_date](Pass::relevant_date) on supported devices.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub relevant_dates: Vec<RelevantDate>,
+
     /// The date and time the pass expires.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -107,6 +145,11 @@ pub struct Pass {
     #[serde(rename = "appLaunchURL")]
     pub app_launch_url: Option<String>,
 
+    /// Links and contacts of the event for poster event ticket (iOS 18)
+    #[serde(default)]
+    #[serde(flatten)]
+    pub event_guide: EventGuide,
+
     /// An array of App Store identifiers for apps associated with the pass.
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -156,6 +199,8 @@ pub struct Pass {
     pub beacons: Vec<Beacon>,
 
     // An array of up to 10 geographic locations the system uses to show a relevant pass.
+    //
+    // Other locations are ignored, see [validate](Pass::validate).
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub locations: Vec<Location>,
@@ -181,8 +226,14 @@ pub struct Pass {
     /// Groups of visible fields that display information on the front and back of a pass.
     #[serde(flatten)]
     pub fields: fields::Type,
-    // TODO: UserInfo
-    // custom JSON
+
+    /// Custom information for companion apps. This data isn’t displayed to the user.
+    ///
+    /// For example, a pass for a cafe can include information about the user’s favorite drink and sandwich in a machine-readable form.
+    /// The companion app uses the data for placing an order for “the usual.”
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_info: Option<serde_json::Value>,
 }
 
 impl Pass {
@@ -250,10 +301,58 @@ impl Pass {
     /// let json = pass.make_json().unwrap();
     /// assert_eq!(json_expected, json);
     /// ```
-    pub fn from_json(data: &str) -> Result<Self, serde_json::Error> {
-        let pass: Pass = serde_json::from_str(data)?;
+    pub fn from_json(data: &str) -> Result<Self, Error> {
+        let pass: Pass = serde_json::from_str(data).map_err(|source| Error::Json {
+            file: String::from("pass.json"),
+            source,
+        })?;
         Ok(pass)
     }
+
+    /// Check pass against Apple's rules, that are not enforced by types.
+    ///
+    /// Returns list of found problems (empty for valid pass). Resources are checked by [Package::validate](crate::Package::validate).
+    ///
+    /// ```
+    /// use passes::{fields, PassBuilder, PassConfig};
+    ///
+    /// let pass = PassBuilder::new(PassConfig {
+    ///     organization_name: String::from("Apple inc."),
+    ///     description: String::from("Example pass"),
+    ///     pass_type_identifier: String::from("com.example.pass"),
+    ///     team_identifier: String::from("AA00AA0A0A"),
+    ///     serial_number: String::from("ABCDEFG1234567890"),
+    /// })
+    /// .fields(
+    ///     fields::Type::Generic {
+    ///         pass_fields: Default::default(),
+    ///     }
+    ///     .add_primary_field(fields::Content::new("gate", "A1", Default::default()))
+    ///     .add_back_field(fields::Content::new("gate", "A1", Default::default())),
+    /// )
+    /// .build();
+    ///
+    /// let diagnostics = pass.validate();
+    /// assert_eq!(1, diagnostics.len());
+    /// assert_eq!("field key gate is repeated in backFields", diagnostics[0].to_string());
+    /// ```
+    pub fn validate(&self) -> Vec<validation::Diagnostic> {
+        validation::validate_pass(self)
+    }
+
+    /// Fields, that Wallet doesn't display on the front of a pass.
+    ///
+    /// Layout depends on pass style, images and first barcode, see [hidden_fields](fields::Type::hidden_fields).
+    pub fn hidden_fields(
+        &self,
+        images: &fields::ImageSet,
+    ) -> Vec<(fields::FieldGroup, &fields::Content)> {
+        let square_barcode = self
+            .barcodes
+            .first()
+            .is_some_and(|barcode| barcode.format.is_square());
+        self.fields.hidden_fields(images, square_barcode)
+    }
 }
 
 /// Builder for pass (represents pass.json file)
@@ -271,10 +370,16 @@ impl PassBuilder {
             // Setup default optional vars
             grouping_identifier: None,
             appearance: None,
+            preferred_style_schemes: Vec::new(),
+            suppress_header_darkening: false,
+            use_automatic_colors: false,
             logo_text: None,
+            event_logo_text: None,
             relevant_date: None,
+            relevant_dates: Vec::new(),
             expiration_date: None,
             app_launch_url: None,
+            event_guide: Default::default(),
             associated_store_identifiers: Vec::new(),
             web_service: None,
             sharing_prohibited: false,
@@ -291,6 +396,7 @@ impl PassBuilder {
                     ..Default::default()
                 },
             },
+            user_info: None,
         };
         Self { pass }
     }
@@ -307,12 +413,36 @@ impl PassBuilder {
         self
     }
 
+    /// Adding [StyleScheme] to [preferred_style_schemes](Pass::preferred_style_schemes)
+    pub fn add_preferred_style_scheme(mut self, scheme: StyleScheme) -> PassBuilder {
+        self.pass.preferred_style_schemes.push(scheme);
+        self
+    }
+
+    /// Adding [suppress_header_darkening](Pass::suppress_header_darkening)
+    pub fn set_suppress_header_darkening(mut self, field: bool) -> PassBuilder {
+        self.pass.suppress_header_darkening = field;
+        self
+    }
+
+    /// Adding [use_automatic_colors](Pass::use_automatic_colors)
+    pub fn set_use_automatic_colors(mut self, field: bool) -> PassBuilder {
+        self.pass.use_automatic_colors = field;
+        self
+    }
+
     /// Adding [logo_text](Pass::logo_text)
     pub fn logo_text(mut self, field: String) -> PassBuilder {
         self.pass.logo_text = Some(field);
         self
     }
 
+    /// Adding [event_logo_text](Pass::event_logo_text)
+    pub fn event_logo_text(mut self, field: String) -> PassBuilder {
+        self.pass.event_logo_text = Some(field);
+        self
+    }
+
     /// Adding [relevant_date](Pass::relevant_date)
     ///
     /// ```
@@ -332,6 +462,12 @@ impl PassBuilder {
         self
     }
 
+    /// Adding [RelevantDate] to [relevant_dates](Pass::relevant_dates)
+    pub fn add_relevant_date(mut self, field: RelevantDate) -> PassBuilder {
+        self.pass.relevant_dates.push(field);
+        self
+    }
+
     /// Adding [expiration_date](Pass::expiration_date)
     pub fn expiration_date(mut self, field: DateTime<Utc>) -> PassBuilder {
         self.pass.expiration_date = Some(field);
@@ -344,6 +480,12 @@ impl PassBuilder {
         self
     }
 
+    /// Adding [event_guide](Pass::event_guide)
+    pub fn event_guide(mut self, field: EventGuide) -> PassBuilder {
+        self.pass.event_guide = field;
+        self
+    }
+
     /// Adding [associated_store_identifiers](Pass::associated_store_identifiers)
     pub fn add_associated_store_identifier(mut self, id: i32) -> PassBuilder {
         self.pass.associated_store_identifiers.push(id);
@@ -387,11 +529,9 @@ impl PassBuilder {
     }
 
     /// Adding [Location] to [locations](Pass::locations)
+    ///
+    /// Wallet uses only first 10 locations, [validate](Pass::validate) reports exceeding.
     pub fn add_location(mut self, location: Location) -> PassBuilder {
-        assert!(
-            self.pass.locations.len() < 10,
-            "Reached limit for geographic locations (maximum - 10)"
-        );
         self.pass.locations.push(location);
         self
     }
@@ -475,6 +615,46 @@ impl PassBuilder {
         self
     }
 
+    /// Adding [user_info](Pass::user_info)
+    pub fn user_info(mut self, field: serde_json::Value) -> PassBuilder {
+        self.pass.user_info = Some(field);
+        self
+    }
+
+    /// Adding [user_info](Pass::user_info) from any serializable data
+    ///
+    /// ```
+    /// use passes::{PassBuilder, PassConfig};
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Customer {
+    ///     tier: String,
+    ///     internal_id: u64,
+    /// }
+    ///
+    /// let pass = PassBuilder::new(PassConfig {
+    ///     organization_name: String::from("Apple inc."),
+    ///     description: String::from("Example pass"),
+    ///     pass_type_identifier: String::from("com.example.pass"),
+    ///     team_identifier: String::from("AA00AA0A0A"),
+    ///     serial_number: String::from("ABCDEFG1234567890"),
+    /// })
+    /// .user_info_from(&Customer {
+    ///     tier: String::from("gold"),
+    ///     internal_id: 42,
+    /// })
+    /// .unwrap()
+    /// .build();
+    /// ```
+    pub fn user_info_from<T: Serialize>(self, data: &T) -> Result<PassBuilder, Error> {
+        let field = serde_json::to_value(data).map_err(|source| Error::Json {
+            file: String::from("pass.json"),
+            source,
+        })?;
+        Ok(self.user_info(field))
+    }
+
     /// Makes `Pass`.
     pub fn build(self) -> Pass {
         self.pass
@@ -482,6 +662,7 @@ impl PassBuilder {
 }
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module, clippy::zero_prefixed_literal)]
 mod tests {
     use chrono::prelude::*;
     use tests::{fields, semantic_tags::SemanticTagLocation, visual_appearance::Color};
@@ -543,6 +724,7 @@ mod tests {
             label_color: None,
             foreground_color: Color::new(250, 10, 10),
             background_color: Color::white(),
+            footer_background_color: None,
         })
         .logo_text(String::from("Test pass"))
         .relevant_date(Utc.with_ymd_and_hms(2024, 02, 07, 0, 0, 0).unwrap())
@@ -750,6 +932,177 @@ mod tests {
         let json = pass.make_json().unwrap();
         assert_eq!(json_expected, json);
     }
+
+    #[test]
+    fn make_pass_with_user_info() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        #[serde(rename_all = "camelCase")]
+        struct Customer {
+            tier: String,
+            internal_id: u64,
+        }
+
+        // Serialization test
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: String::from("Apple inc."),
+            description: String::from("Example pass"),
+            pass_type_identifier: String::from("com.example.pass"),
+            team_identifier: String::from("AA00AA0A0A"),
+            serial_number: String::from("ABCDEFG1234567890"),
+        })
+        .user_info_from(&Customer {
+            tier: String::from("gold"),
+            internal_id: 42,
+        })
+        .unwrap()
+        .build();
+
+        let json = pass.make_json().unwrap();
+
+        println!("{}", json);
+
+        let json_expected = r#"{
+  "formatVersion": 1,
+  "organizationName": "Apple inc.",
+  "description": "Example pass",
+  "passTypeIdentifier": "com.example.pass",
+  "teamIdentifier": "AA00AA0A0A",
+  "serialNumber": "ABCDEFG1234567890",
+  "generic": {
+    "auxiliaryFields": [],
+    "backFields": [],
+    "headerFields": [],
+    "primaryFields": [],
+    "secondaryFields": []
+  },
+  "userInfo": {
+    "internalId": 42,
+    "tier": "gold"
+  }
+}"#;
+
+        assert_eq!(json_expected, json);
+
+        // Deserialization test
+        let pass: Pass = Pass::from_json(json_expected).unwrap();
+        let json = pass.make_json().unwrap();
+        assert_eq!(json_expected, json);
+
+        let customer: Customer = serde_json::from_value(pass.user_info.unwrap()).unwrap();
+        assert_eq!("gold", customer.tier);
+        assert_eq!(42, customer.internal_id);
+    }
+
+    #[test]
+    fn make_poster_event_ticket() {
+        use crate::pass::{
+            event_guide::EventGuide,
+            relevant_date::RelevantDate,
+            semantic_tags::{SemanticTagEventDateInfo, SemanticTagSeat},
+            visual_appearance::StyleScheme,
+        };
+
+        let start = Utc.with_ymd_and_hms(2024, 9, 1, 18, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 9, 1, 23, 0, 0).unwrap();
+
+        // Serialization test
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: String::from("Apple inc."),
+            description: String::from("Example pass"),
+            pass_type_identifier: String::from("com.example.pass"),
+            team_identifier: String::from("AA00AA0A0A"),
+            serial_number: String::from("ABCDEFG1234567890"),
+        })
+        .add_preferred_style_scheme(StyleScheme::PosterEventTicket)
+        .add_preferred_style_scheme(StyleScheme::EventTicket)
+        .set_use_automatic_colors(true)
+        .event_logo_text(String::from("Concert"))
+        .add_relevant_date(RelevantDate::interval(start, end))
+        .event_guide(EventGuide {
+            bag_policy_url: Some(String::from("https://example.com/bags")),
+            parking_information_url: Some(String::from("https://example.com/parking")),
+            sell_url: Some(String::from("https://example.com/sell")),
+            ..Default::default()
+        })
+        .semantics(SemanticTags {
+            event_name: Some(String::from("Concert")),
+            event_live_message: Some(String::from("Enjoy the show!")),
+            event_start_date_info: Some(SemanticTagEventDateInfo {
+                time_zone: Some(String::from("Europe/Moscow")),
+                ..SemanticTagEventDateInfo::new(start)
+            }),
+            additional_ticket_attributes: Some(String::from("Standing")),
+            venue_doors_open_date: Some(start),
+            seats: vec![SemanticTagSeat {
+                seat_section: Some(String::from("A")),
+                seat_section_color: Color::new(23, 187, 82),
+                ..Default::default()
+            }],
+            ..Default::default()
+        })
+        .fields(fields::Type::EventTicket {
+            pass_fields: Default::default(),
+        })
+        .build();
+
+        let json = pass.make_json().unwrap();
+
+        println!("{}", json);
+
+        let json_expected = r#"{
+  "formatVersion": 1,
+  "organizationName": "Apple inc.",
+  "description": "Example pass",
+  "passTypeIdentifier": "com.example.pass",
+  "teamIdentifier": "AA00AA0A0A",
+  "serialNumber": "ABCDEFG1234567890",
+  "preferredStyleSchemes": [
+    "posterEventTicket",
+    "eventTicket"
+  ],
+  "useAutomaticColors": true,
+  "eventLogoText": "Concert",
+  "relevantDates": [
+    {
+      "startDate": "2024-09-01T18:00:00+00:00",
+      "endDate": "2024-09-01T23:00:00+00:00"
+    }
+  ],
+  "bagPolicyURL": "https://example.com/bags",
+  "parkingInformationURL": "https://example.com/parking",
+  "sellURL": "https://example.com/sell",
+  "semantics": {
+    "additionalTicketAttributes": "Standing",
+    "eventLiveMessage": "Enjoy the show!",
+    "eventName": "Concert",
+    "eventStartDateInfo": {
+      "date": "2024-09-01T18:00:00+00:00",
+      "timeZone": "Europe/Moscow"
+    },
+    "seats": [
+      {
+        "seatSection": "A",
+        "seatSectionColor": "rgb(23, 187, 82)"
+      }
+    ],
+    "venueDoorsOpenDate": "2024-09-01T18:00:00+00:00"
+  },
+  "eventTicket": {
+    "auxiliaryFields": [],
+    "backFields": [],
+    "headerFields": [],
+    "primaryFields": [],
+    "secondaryFields": []
+  }
+}"#;
+
+        assert_eq!(json_expected, json);
+
+        // Deserialization test
+        let pass: Pass = Pass::from_json(json_expected).unwrap();
+        let json = pass.make_json().unwrap();
+        assert_eq!(json_expected, json);
+    }
 }
 
 // For serde skipping - if boolean false
@@ -1,5 +1,11 @@
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "barcode")]
+mod render;
+
+#[cfg(feature = "barcode")]
+pub use self::render::RenderOptions;
+
 /// Represents a barcode on a pass.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -55,6 +61,13 @@ pub enum BarcodeFormat {
     Code128,
 }
 
+impl BarcodeFormat {
+    /// Check that barcode is square (QR and Aztec), it changes layout of pass fields
+    pub fn is_square(&self) -> bool {
+        matches!(self, BarcodeFormat::QR | BarcodeFormat::Aztec)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
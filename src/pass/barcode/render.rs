@@ -0,0 +1,278 @@
+use std::fmt::Write;
+
+use rxing::{common::BitMatrix, common::CharacterSet, EncodeHints, MultiFormatWriter, Writer};
+
+use crate::Error;
+
+use super::{Barcode, BarcodeFormat};
+
+/// Options for barcode rendering
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Size of one module (smallest bar or square) in pixels, for SVG in user units.
+    pub module_size: u32,
+
+    /// Empty space around barcode, in modules.
+    pub quiet_zone: u32,
+
+    /// Height of bars for Code128, in modules.
+    pub bar_height: u32,
+}
+
+impl Default for RenderOptions {
+    /// Creates options with 4 px modules and 4 modules of quiet zone
+    fn default() -> Self {
+        Self {
+            module_size: 4,
+            quiet_zone: 4,
+            bar_height: 40,
+        }
+    }
+}
+
+impl Barcode {
+    /// Render barcode as grayscale PNG image.
+    ///
+    /// Requires `barcode` feature. Message is encoded with [message_encoding](Barcode::message_encoding).
+    ///
+    /// ```
+    /// use passes::barcode::{Barcode, BarcodeFormat, RenderOptions};
+    ///
+    /// let barcode = Barcode {
+    ///     message: String::from("Hello world!"),
+    ///     format: BarcodeFormat::QR,
+    ///     ..Default::default()
+    /// };
+    /// let png = barcode.render_png(&RenderOptions::default()).unwrap();
+    /// assert_eq!(b"\x89PNG", &png[..4]);
+    /// ```
+    pub fn render_png(&self, options: &RenderOptions) -> Result<Vec<u8>, Error> {
+        let modules = self.modules(options)?;
+        let scale = options.module_size.max(1) as usize;
+        let width = modules.width * scale;
+        let height = modules.height * scale;
+
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let dark = modules.is_dark(x / scale, y / scale);
+                pixels.push(if dark { 0 } else { 255 });
+            }
+        }
+
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(|err| Error::Barcode {
+                message: format!("can't encode PNG: {}", err),
+            })?;
+        Ok(data)
+    }
+
+    /// Render barcode as SVG image.
+    ///
+    /// Requires `barcode` feature. Message is encoded with [message_encoding](Barcode::message_encoding).
+    ///
+    /// ```
+    /// use passes::barcode::{Barcode, BarcodeFormat, RenderOptions};
+    ///
+    /// let barcode = Barcode {
+    ///     message: String::from("Hello world!"),
+    ///     format: BarcodeFormat::Code128,
+    ///     ..Default::default()
+    /// };
+    /// let svg = barcode.render_svg(&RenderOptions::default()).unwrap();
+    /// assert!(svg.starts_with("<svg"));
+    /// ```
+    pub fn render_svg(&self, options: &RenderOptions) -> Result<String, Error> {
+        let modules = self.modules(options)?;
+        let scale = options.module_size.max(1) as usize;
+
+        let mut path = String::new();
+        for y in 0..modules.height {
+            // Join adjacent dark modules of row into one rectangle
+            let mut x = 0;
+            while x < modules.width {
+                if !modules.is_dark(x, y) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < modules.width && modules.is_dark(x, y) {
+                    x += 1;
+                }
+                let _ = write!(path, "M{},{}h{}v1h-{}z", start, y, x - start, x - start);
+            }
+        }
+
+        Ok(format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+                r##"<rect width="100%" height="100%" fill="#fff"/><path d="{}" fill="#000"/></svg>"##
+            ),
+            modules.width * scale,
+            modules.height * scale,
+            modules.width,
+            modules.height,
+            path
+        ))
+    }
+
+    /// Encode message to modules, with quiet zone
+    fn modules(&self, options: &RenderOptions) -> Result<Modules, Error> {
+        if CharacterSet::get_character_set_by_name(&self.message_encoding).is_none() {
+            return Err(Error::Barcode {
+                message: format!("unsupported message encoding {}", self.message_encoding),
+            });
+        }
+
+        let format = match self.format {
+            BarcodeFormat::QR => rxing::BarcodeFormat::QR_CODE,
+            BarcodeFormat::PDF417 => rxing::BarcodeFormat::PDF_417,
+            BarcodeFormat::Aztec => rxing::BarcodeFormat::AZTEC,
+            BarcodeFormat::Code128 => rxing::BarcodeFormat::CODE_128,
+        };
+        let hints = EncodeHints {
+            CharacterSet: Some(self.message_encoding.clone()),
+            // Quiet zone is added by renderer, same for all formats
+            Margin: Some(String::from("0")),
+            ..Default::default()
+        };
+        let matrix = MultiFormatWriter
+            .encode_with_hints(&self.message, &format, 0, 0, &hints)
+            .map_err(|err| Error::Barcode {
+                message: format!("can't encode message: {}", err),
+            })?;
+
+        // Linear barcode is encoded as one row
+        let row_height = if matrix.getHeight() == 1 {
+            options.bar_height.max(1)
+        } else {
+            1
+        };
+        Ok(Modules {
+            width: (matrix.getWidth() + options.quiet_zone * 2) as usize,
+            height: (matrix.getHeight() * row_height + options.quiet_zone * 2) as usize,
+            quiet_zone: options.quiet_zone as usize,
+            row_height: row_height as usize,
+            matrix,
+        })
+    }
+}
+
+/// Encoded barcode with quiet zone
+struct Modules {
+    width: usize,
+    height: usize,
+    quiet_zone: usize,
+    row_height: usize,
+    matrix: BitMatrix,
+}
+
+impl Modules {
+    fn is_dark(&self, x: usize, y: usize) -> bool {
+        let (Some(x), Some(y)) = (
+            x.checked_sub(self.quiet_zone),
+            y.checked_sub(self.quiet_zone),
+        ) else {
+            return false;
+        };
+        let y = y / self.row_height;
+        x < self.matrix.getWidth() as usize
+            && y < self.matrix.getHeight() as usize
+            && self.matrix.get(x as u32, y as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rxing::helpers::detect_in_luma;
+
+    use super::*;
+
+    /// Decode rendered PNG
+    fn scan(png: &[u8], format: rxing::BarcodeFormat) -> String {
+        let decoder = png::Decoder::new(std::io::Cursor::new(png));
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        pixels.truncate(info.buffer_size());
+
+        detect_in_luma(pixels, info.width, info.height, Some(format))
+            .unwrap()
+            .getText()
+            .to_string()
+    }
+
+    #[test]
+    fn render_and_scan() {
+        let formats = [
+            (BarcodeFormat::QR, rxing::BarcodeFormat::QR_CODE),
+            (BarcodeFormat::PDF417, rxing::BarcodeFormat::PDF_417),
+            (BarcodeFormat::Aztec, rxing::BarcodeFormat::AZTEC),
+            (BarcodeFormat::Code128, rxing::BarcodeFormat::CODE_128),
+        ];
+        for (format, scan_format) in formats {
+            let barcode = Barcode {
+                message: String::from("Hello world!"),
+                format,
+                ..Default::default()
+            };
+            let png = barcode.render_png(&RenderOptions::default()).unwrap();
+            assert_eq!("Hello world!", scan(&png, scan_format));
+        }
+    }
+
+    #[test]
+    fn render_with_encoding() {
+        let barcode = Barcode {
+            message: String::from("Привет"),
+            format: BarcodeFormat::QR,
+            message_encoding: String::from("utf-8"),
+            ..Default::default()
+        };
+        let png = barcode.render_png(&RenderOptions::default()).unwrap();
+        assert_eq!("Привет", scan(&png, rxing::BarcodeFormat::QR_CODE));
+
+        let barcode = Barcode {
+            message_encoding: String::from("x-unknown"),
+            ..barcode
+        };
+        let err = barcode.render_png(&RenderOptions::default()).err().unwrap();
+        assert!(matches!(err, Error::Barcode { .. }));
+        assert_eq!(
+            "barcode error: unsupported message encoding x-unknown",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn render_svg() {
+        let barcode = Barcode {
+            message: String::from("A"),
+            format: BarcodeFormat::Code128,
+            ..Default::default()
+        };
+        let options = RenderOptions {
+            module_size: 2,
+            quiet_zone: 10,
+            bar_height: 20,
+        };
+        let svg = barcode.render_svg(&options).unwrap();
+
+        // Code128 "A": start, data, checksum (11 modules each), stop (13 modules)
+        let width = 11 * 3 + 13 + 20;
+        assert!(svg.contains(&format!(
+            r#"width="{}" height="{}" viewBox="0 0 {} {}""#,
+            width * 2,
+            40 * 2,
+            width,
+            40
+        )));
+        assert!(svg.contains("M10,10h2v1h-2z"));
+    }
+}
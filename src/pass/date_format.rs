@@ -2,6 +2,7 @@ use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::{self, Deserialize, Deserializer, Serializer};
 
 /// Serialization to custom date format
+#[allow(clippy::useless_format)]
 pub fn serialize<S>(date: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -15,22 +16,44 @@ pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::
 where
     D: Deserializer<'de>,
 {
-    let s = String::deserialize(deserializer)?;
-    if let Ok(dt) = DateTime::parse_from_rfc3339(&s) {
-        // RFC3339
-        Ok(Some(dt.with_timezone(&Utc)))
-    } else if let Ok(dt) = DateTime::parse_from_rfc2822(&s) {
-        // RFC2822
-        Ok(Some(dt.with_timezone(&Utc)))
-    } else if let Ok(dt) = NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S") {
-        // Custom naive format
-        Ok(Some(DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc)))
-    } else {
-        Err(serde::de::Error::custom("Invalid DateTime format"))
+    required::deserialize(deserializer).map(Some)
+}
+
+/// Same format for required (not optional) dates
+pub mod required {
+    use super::*;
+
+    /// Serialization to custom date format
+    pub fn serialize<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&date.to_rfc3339())
+    }
+
+    /// Deserialization from custom date format
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        if let Ok(dt) = DateTime::parse_from_rfc3339(&s) {
+            // RFC3339
+            Ok(dt.with_timezone(&Utc))
+        } else if let Ok(dt) = DateTime::parse_from_rfc2822(&s) {
+            // RFC2822
+            Ok(dt.with_timezone(&Utc))
+        } else if let Ok(dt) = NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S") {
+            // Custom naive format
+            Ok(DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc))
+        } else {
+            Err(serde::de::Error::custom("Invalid DateTime format"))
+        }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::zero_prefixed_literal)]
 mod tests {
     use super::*;
     use chrono::{prelude::*, DateTime, Utc};
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+/// Links and contacts of the event, displayed in event guide of poster event ticket (iOS 18).
+///
+/// Wallet shows a button for every provided key.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EventGuide {
+    /// A URL for information about accessibility at the event venue.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "accessibilityURL")]
+    pub accessibility_url: Option<String>,
+
+    /// A URL for purchasing add-ons, such as upgrades or VIP experiences.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "addOnURL")]
+    pub add_on_url: Option<String>,
+
+    /// A URL for information about the bag policy of the event venue.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "bagPolicyURL")]
+    pub bag_policy_url: Option<String>,
+
+    /// The email address for contacting the event venue.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_venue_email: Option<String>,
+
+    /// The phone number for contacting the event venue.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_venue_phone_number: Option<String>,
+
+    /// A URL of the event venue website.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contact_venue_website: Option<String>,
+
+    /// A URL for directions to the event venue.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "directionsInformationURL")]
+    pub directions_information_url: Option<String>,
+
+    /// A URL for purchasing merchandise of the event.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "merchandiseURL")]
+    pub merchandise_url: Option<String>,
+
+    /// A URL for ordering food at the event venue.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "orderFoodURL")]
+    pub order_food_url: Option<String>,
+
+    /// A URL for information about parking at the event venue.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "parkingInformationURL")]
+    pub parking_information_url: Option<String>,
+
+    /// A URL for purchasing parking at the event venue.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "purchaseParkingURL")]
+    pub purchase_parking_url: Option<String>,
+
+    /// A URL for reselling the ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "sellURL")]
+    pub sell_url: Option<String>,
+
+    /// A URL for transferring the ticket to another person.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "transferURL")]
+    pub transfer_url: Option<String>,
+
+    /// A URL for information about public transit to the event venue.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "transitInformationURL")]
+    pub transit_information_url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn make_event_guide() {
+        // Serialization test
+        let guide = EventGuide {
+            bag_policy_url: Some(String::from("https://example.com/bags")),
+            contact_venue_phone_number: Some(String::from("+1 555 0100")),
+            order_food_url: Some(String::from("https://example.com/food")),
+            transfer_url: Some(String::from("https://example.com/transfer")),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string_pretty(&guide).unwrap();
+
+        let json_expected = r#"{
+  "bagPolicyURL": "https://example.com/bags",
+  "contactVenuePhoneNumber": "+1 555 0100",
+  "orderFoodURL": "https://example.com/food",
+  "transferURL": "https://example.com/transfer"
+}"#;
+        assert_eq!(json_expected, json);
+
+        // Deserialization test
+        let guide: EventGuide = serde_json::from_str(json_expected).unwrap();
+        let json = serde_json::to_string_pretty(&guide).unwrap();
+        assert_eq!(json_expected, json);
+    }
+}
@@ -1,5 +1,8 @@
+use std::fmt;
+
+use chrono::{DateTime, FixedOffset};
 use is_empty::IsEmpty;
-use serde::{Deserialize, Serialize};
+use serde::{de::IgnoredAny, Deserialize, Deserializer, Serialize, Serializer};
 
 use super::semantic_tags::SemanticTags;
 
@@ -21,6 +24,22 @@ pub struct Fields {
 
     /// Represents the fields that display supporting information on the front of a pass.
     pub secondary_fields: Vec<Content>,
+
+    /// Set on parsing, if pass.json has `transitType` in other style than boarding pass.
+    ///
+    /// It's not allowed, [Pass::validate](crate::Pass::validate) reports it. Never serialized.
+    #[serde(rename = "transitType")]
+    #[serde(default, skip_serializing, deserialize_with = "is_present")]
+    pub stray_transit_type: bool,
+}
+
+/// Mark key as present, ignoring its value
+fn is_present<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    IgnoredAny::deserialize(deserializer)?;
+    Ok(true)
 }
 
 impl Default for Fields {
@@ -32,19 +51,119 @@ impl Default for Fields {
             header_fields: Vec::new(),
             primary_fields: Vec::new(),
             secondary_fields: Vec::new(),
+            stray_transit_type: false,
+        }
+    }
+}
+
+impl Fields {
+    /// Fields of the group
+    pub fn group(&self, group: FieldGroup) -> &Vec<Content> {
+        match group {
+            FieldGroup::Header => &self.header_fields,
+            FieldGroup::Primary => &self.primary_fields,
+            FieldGroup::Secondary => &self.secondary_fields,
+            FieldGroup::Auxiliary => &self.auxiliary_fields,
+            FieldGroup::Back => &self.back_fields,
         }
     }
+
+    /// Mutable fields of the group
+    pub fn group_mut(&mut self, group: FieldGroup) -> &mut Vec<Content> {
+        match group {
+            FieldGroup::Header => &mut self.header_fields,
+            FieldGroup::Primary => &mut self.primary_fields,
+            FieldGroup::Secondary => &mut self.secondary_fields,
+            FieldGroup::Auxiliary => &mut self.auxiliary_fields,
+            FieldGroup::Back => &mut self.back_fields,
+        }
+    }
+
+    /// All fields of the pass with their groups, front fields first
+    pub fn iter(&self) -> impl Iterator<Item = (FieldGroup, &Content)> {
+        FieldGroup::ALL
+            .into_iter()
+            .flat_map(move |group| self.group(group).iter().map(move |field| (group, field)))
+    }
+
+    /// All mutable fields of the pass with their groups, front fields first
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (FieldGroup, &mut Content)> {
+        [
+            (FieldGroup::Header, &mut self.header_fields),
+            (FieldGroup::Primary, &mut self.primary_fields),
+            (FieldGroup::Secondary, &mut self.secondary_fields),
+            (FieldGroup::Auxiliary, &mut self.auxiliary_fields),
+            (FieldGroup::Back, &mut self.back_fields),
+        ]
+        .into_iter()
+        .flat_map(|(group, fields)| fields.iter_mut().map(move |field| (group, field)))
+    }
+}
+
+/// Group of fields on a pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldGroup {
+    /// Fields at the top of a pass ([header_fields](Fields::header_fields))
+    Header,
+    /// The most important fields ([primary_fields](Fields::primary_fields))
+    Primary,
+    /// Supporting fields ([secondary_fields](Fields::secondary_fields))
+    Secondary,
+    /// Additional fields ([auxiliary_fields](Fields::auxiliary_fields))
+    Auxiliary,
+    /// Fields on the back of a pass ([back_fields](Fields::back_fields))
+    Back,
+}
+
+impl FieldGroup {
+    /// All groups, in order of appearance on a pass
+    pub const ALL: [FieldGroup; 5] = [
+        FieldGroup::Header,
+        FieldGroup::Primary,
+        FieldGroup::Secondary,
+        FieldGroup::Auxiliary,
+        FieldGroup::Back,
+    ];
+}
+
+impl fmt::Display for FieldGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FieldGroup::Header => "headerFields",
+            FieldGroup::Primary => "primaryFields",
+            FieldGroup::Secondary => "secondaryFields",
+            FieldGroup::Auxiliary => "auxiliaryFields",
+            FieldGroup::Back => "backFields",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Images of the pass, that change layout of fields
+///
+/// Usually made from package resources with [Package::image_set](crate::Package::image_set).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImageSet {
+    /// Pass has background image (background.png)
+    pub background: bool,
+
+    /// Pass has strip image (strip.png)
+    pub strip: bool,
+
+    /// Pass has thumbnail image (thumbnail.png)
+    pub thumbnail: bool,
 }
 
 /// Represents the information to display in a field on a pass.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
+#[serde(from = "RawContent")]
 pub struct Content {
     /// (Required) A unique key that identifies a field in the pass; for example, “departure-gate”.
     pub key: String,
 
     /// (Required) The value to use for the field; for example, 42. A date or time value must include a time zone.
-    pub value: String,
+    pub value: FieldValue,
 
     /// All optionals
     #[serde(flatten)]
@@ -56,10 +175,227 @@ impl Content {
     pub fn new(key: &str, value: &str, options: ContentOptions) -> Self {
         Self {
             key: String::from(key),
-            value: String::from(value),
-            options: options,
+            value: FieldValue::Text(String::from(value)),
+            options,
         }
     }
+
+    /// Creates field with date, formatted by Wallet with `date_style` and `time_style`.
+    ///
+    /// ```
+    /// use chrono::{FixedOffset, TimeZone};
+    /// use passes::fields::{Content, DateStyle};
+    ///
+    /// let date = FixedOffset::east_opt(3 * 3600)
+    ///     .unwrap()
+    ///     .with_ymd_and_hms(2024, 2, 7, 19, 30, 0)
+    ///     .unwrap();
+    /// let field = Content::new_date("doors", date, DateStyle::Medium, DateStyle::Short, Default::default());
+    ///
+    /// let json = serde_json::to_string(&field).unwrap();
+    /// assert_eq!(
+    ///     r#"{"key":"doors","value":"2024-02-07T19:30:00+03:00","dateStyle":"PKDateStyleMedium","timeStyle":"PKDateStyleShort"}"#,
+    ///     json
+    /// );
+    /// ```
+    pub fn new_date(
+        key: &str,
+        value: DateTime<FixedOffset>,
+        date_style: DateStyle,
+        time_style: DateStyle,
+        options: ContentOptions,
+    ) -> Self {
+        Self {
+            key: String::from(key),
+            value: FieldValue::Date(value),
+            options: ContentOptions {
+                date_style: Some(date_style),
+                time_style: Some(time_style),
+                ..options
+            },
+        }
+    }
+
+    /// Creates field with number, formatted by Wallet with `number_style`.
+    ///
+    /// Number must be finite, NaN and infinity fail on writing pass.json ([Error::Json](crate::Error::Json)).
+    pub fn new_number(
+        key: &str,
+        value: f64,
+        number_style: NumberStyle,
+        options: ContentOptions,
+    ) -> Self {
+        Self {
+            key: String::from(key),
+            value: FieldValue::Number(value),
+            options: ContentOptions {
+                number_style: Some(number_style),
+                currency_code: None,
+                ..options
+            },
+        }
+    }
+
+    /// Creates field with currency amount, `currency_code` is ISO 4217 code, e.g. `USD`.
+    ///
+    /// Amount must be finite, same as for [new_number](Content::new_number).
+    ///
+    /// ```
+    /// use passes::fields::Content;
+    ///
+    /// let field = Content::new_currency("balance", 25.5, "USD", Default::default());
+    ///
+    /// let json = serde_json::to_string(&field).unwrap();
+    /// assert_eq!(r#"{"key":"balance","value":25.5,"currencyCode":"USD"}"#, json);
+    /// ```
+    pub fn new_currency(
+        key: &str,
+        amount: f64,
+        currency_code: &str,
+        options: ContentOptions,
+    ) -> Self {
+        Self {
+            key: String::from(key),
+            value: FieldValue::Currency(amount),
+            options: ContentOptions {
+                currency_code: Some(String::from(currency_code)),
+                number_style: None,
+                ..options
+            },
+        }
+    }
+}
+
+/// Content of field, as it is read from pass.json
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawContent {
+    key: String,
+    value: RawValue,
+    #[serde(flatten)]
+    options: ContentOptions,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawValue {
+    Number(f64),
+    Text(String),
+}
+
+impl From<RawContent> for Content {
+    /// Dates and currency amounts are recognized by styles of field
+    fn from(raw: RawContent) -> Self {
+        let options = raw.options;
+        let value = match raw.value {
+            RawValue::Number(number) if options.currency_code.is_some() => {
+                FieldValue::Currency(number)
+            }
+            RawValue::Number(number) => FieldValue::Number(number),
+            RawValue::Text(text)
+                if options.date_style.is_some() || options.time_style.is_some() =>
+            {
+                match parse_date(&text) {
+                    Some(date) => FieldValue::Date(date),
+                    None => FieldValue::Text(text),
+                }
+            }
+            RawValue::Text(text) => FieldValue::Text(text),
+        };
+        Self {
+            key: raw.key,
+            value,
+            options,
+        }
+    }
+}
+
+/// Parse ISO 8601 date with time zone, seconds are optional
+fn parse_date(text: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(text)
+        .or_else(|_| DateTime::parse_from_str(text, "%Y-%m-%dT%H:%M%#z"))
+        .ok()
+}
+
+/// Value of field.
+///
+/// Wallet formats dates with [date_style](ContentOptions::date_style) and [time_style](ContentOptions::time_style),
+/// numbers with [number_style](ContentOptions::number_style) and currency amounts with [currency_code](ContentOptions::currency_code).
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    /// Localizable string
+    Text(String),
+
+    /// Number, serialized as JSON number. NaN and infinity can't be serialized.
+    Number(f64),
+
+    /// Date and time with time zone, serialized as ISO 8601 string
+    Date(DateTime<FixedOffset>),
+
+    /// Currency amount, serialized as JSON number. Currency is set by [currency_code](ContentOptions::currency_code).
+    /// NaN and infinity can't be serialized.
+    Currency(f64),
+}
+
+impl Serialize for FieldValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            FieldValue::Text(text) => serializer.serialize_str(text),
+            FieldValue::Date(date) => serializer.serialize_str(&date.to_rfc3339()),
+            FieldValue::Number(number) | FieldValue::Currency(number) => {
+                // JSON has no NaN and infinity, serde_json writes them as null
+                if !number.is_finite() {
+                    return Err(serde::ser::Error::custom(format!(
+                        "field value {} is not a finite number",
+                        number
+                    )));
+                }
+                // Keep integers without fractional part, e.g. 42 instead of 42.0
+                if number.fract() == 0.0 && number.abs() < i64::MAX as f64 {
+                    serializer.serialize_i64(*number as i64)
+                } else {
+                    serializer.serialize_f64(*number)
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Text(text) => write!(f, "{}", text),
+            FieldValue::Number(number) | FieldValue::Currency(number) => write!(f, "{}", number),
+            FieldValue::Date(date) => write!(f, "{}", date.to_rfc3339()),
+        }
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        FieldValue::Text(String::from(value))
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        FieldValue::Text(value)
+    }
+}
+
+impl From<f64> for FieldValue {
+    fn from(value: f64) -> Self {
+        FieldValue::Number(value)
+    }
+}
+
+impl From<DateTime<FixedOffset>> for FieldValue {
+    fn from(value: DateTime<FixedOffset>) -> Self {
+        FieldValue::Date(value)
+    }
 }
 
 /// Represents options for `FieldContent`
@@ -264,6 +600,12 @@ pub enum Type {
         #[serde(flatten)]
         pass_fields: Fields,
     },
+    /// Represents the groups of fields that display the information for a store card.
+    StoreCard {
+        /// Groups of fields that display information on the front and back of a pass.
+        #[serde(flatten)]
+        pass_fields: Fields,
+    },
 }
 
 /// The type of transit for a boarding pass.
@@ -282,6 +624,174 @@ pub enum TransitType {
 }
 
 impl Type {
+    /// Groups of fields, for any pass style
+    pub fn pass_fields(&self) -> &Fields {
+        match self {
+            Self::BoardingPass { pass_fields, .. }
+            | Self::Coupon { pass_fields }
+            | Self::EventTicket { pass_fields }
+            | Self::Generic { pass_fields }
+            | Self::StoreCard { pass_fields } => pass_fields,
+        }
+    }
+
+    /// Mutable groups of fields, for any pass style
+    pub fn pass_fields_mut(&mut self) -> &mut Fields {
+        match self {
+            Self::BoardingPass { pass_fields, .. }
+            | Self::Coupon { pass_fields }
+            | Self::EventTicket { pass_fields }
+            | Self::Generic { pass_fields }
+            | Self::StoreCard { pass_fields } => pass_fields,
+        }
+    }
+
+    /// Every field in every group, see [Fields::iter]
+    pub fn iter(&self) -> impl Iterator<Item = (FieldGroup, &Content)> {
+        self.pass_fields().iter()
+    }
+
+    /// Every mutable field in every group, see [Fields::iter_mut]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (FieldGroup, &mut Content)> {
+        self.pass_fields_mut().iter_mut()
+    }
+
+    /// Find field by key in any group
+    pub fn field(&self, key: &str) -> Option<&Content> {
+        self.iter()
+            .map(|(_, field)| field)
+            .find(|field| field.key == key)
+    }
+
+    /// Find mutable field by key in any group.
+    ///
+    /// Use it for updating existing pass, e.g. read by [Package::read](crate::Package::read).
+    ///
+    /// ```
+    /// use passes::fields::{Content, Type};
+    ///
+    /// let mut fields = Type::Generic {
+    ///     pass_fields: Default::default(),
+    /// }
+    /// .add_back_field(Content::new("terms", "Old terms", Default::default()));
+    ///
+    /// let field = fields.field_mut("terms").unwrap();
+    /// field.options.change_message = Some(String::from("Terms changed: %@"));
+    /// field.value = "New terms".into();
+    /// ```
+    pub fn field_mut(&mut self, key: &str) -> Option<&mut Content> {
+        self.iter_mut()
+            .map(|(_, field)| field)
+            .find(|field| field.key == key)
+    }
+
+    /// Change value of field by key, returns `false` if field is not found.
+    ///
+    /// Styles of field are kept, so date value must be set for date field, etc.
+    ///
+    /// ```
+    /// use passes::fields::{Content, Type};
+    ///
+    /// let mut fields = Type::StoreCard {
+    ///     pass_fields: Default::default(),
+    /// }
+    /// .add_primary_field(Content::new("balance", "$10.00", Default::default()));
+    ///
+    /// assert!(fields.set_value("balance", "$25.00"));
+    /// assert_eq!("$25.00", fields.field("balance").unwrap().value.to_string());
+    /// assert!(!fields.set_value("points", "100"));
+    /// ```
+    pub fn set_value<V: Into<FieldValue>>(&mut self, key: &str, value: V) -> bool {
+        match self.field_mut(key) {
+            Some(field) => {
+                field.value = value.into();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove field by key from any group, returns removed field
+    pub fn remove_field(&mut self, key: &str) -> Option<Content> {
+        let fields = self.pass_fields_mut();
+        FieldGroup::ALL.into_iter().find_map(|group| {
+            let group_fields = fields.group_mut(group);
+            let index = group_fields.iter().position(|field| field.key == key)?;
+            Some(group_fields.remove(index))
+        })
+    }
+
+    /// Move field by key to the end of another group, returns `false` if field is not found
+    pub fn move_field(&mut self, key: &str, group: FieldGroup) -> bool {
+        match self.remove_field(key) {
+            Some(field) => {
+                self.pass_fields_mut().group_mut(group).push(field);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Fields, that Wallet doesn't display on the front of a pass for this style and layout.
+    ///
+    /// Limits for the pass styles:
+    /// * Header fields - up to 3 for all styles.
+    /// * Primary fields - up to 2 for boarding pass, 1 for other styles.
+    /// * Boarding pass - up to 5 secondary and 5 auxiliary fields.
+    /// * Coupon and store card - up to 4 secondary and auxiliary fields, combined.
+    /// * Event ticket - up to 4 secondary and 4 auxiliary fields, or 4 combined with strip image.
+    /// * Generic - up to 4 secondary and 4 auxiliary fields, or 4 combined with square barcode.
+    ///
+    /// Back fields are never hidden.
+    ///
+    /// ```
+    /// use passes::fields::{Content, FieldGroup, ImageSet, Type};
+    ///
+    /// let fields = Type::EventTicket {
+    ///     pass_fields: Default::default(),
+    /// }
+    /// .add_primary_field(Content::new("event", "Concert", Default::default()))
+    /// .add_primary_field(Content::new("artist", "Band", Default::default()));
+    ///
+    /// let hidden = fields.hidden_fields(&ImageSet::default(), false);
+    /// assert_eq!(1, hidden.len());
+    /// assert_eq!(FieldGroup::Primary, hidden[0].0);
+    /// assert_eq!("artist", hidden[0].1.key);
+    /// ```
+    pub fn hidden_fields(
+        &self,
+        images: &ImageSet,
+        square_barcode: bool,
+    ) -> Vec<(FieldGroup, &Content)> {
+        let fields = self.pass_fields();
+
+        let (primary, secondary, auxiliary, combined) = match self {
+            Self::BoardingPass { .. } => (2, 5, 5, None),
+            Self::Coupon { .. } | Self::StoreCard { .. } => (1, 4, 4, Some(4)),
+            Self::EventTicket { .. } => (1, 4, 4, images.strip.then_some(4)),
+            Self::Generic { .. } => (1, 4, 4, square_barcode.then_some(4)),
+        };
+
+        let mut hidden = Vec::new();
+        let mut push_hidden = |group: FieldGroup, limit: usize| {
+            let group_fields = fields.group(group);
+            hidden.extend(group_fields.iter().skip(limit).map(|field| (group, field)));
+            group_fields.len().min(limit)
+        };
+
+        push_hidden(FieldGroup::Header, 3);
+        push_hidden(FieldGroup::Primary, primary);
+        let shown = push_hidden(FieldGroup::Secondary, secondary);
+        // Auxiliary fields fill the rest of the combined row
+        let auxiliary = match combined {
+            Some(combined) => auxiliary.min(combined - shown),
+            None => auxiliary,
+        };
+        push_hidden(FieldGroup::Auxiliary, auxiliary);
+
+        hidden
+    }
+
     /// Add field that display additional information on the front of a pass.
     pub fn add_auxiliary_field(mut self, field: Content) -> Self {
         match self {
@@ -297,6 +807,9 @@ impl Type {
             }
             | Self::Generic {
                 ref mut pass_fields,
+            }
+            | Self::StoreCard {
+                ref mut pass_fields,
             } => pass_fields.auxiliary_fields.push(field),
         }
         self
@@ -317,6 +830,9 @@ impl Type {
             }
             | Self::Generic {
                 ref mut pass_fields,
+            }
+            | Self::StoreCard {
+                ref mut pass_fields,
             } => pass_fields.back_fields.push(field),
         }
         self
@@ -337,6 +853,9 @@ impl Type {
             }
             | Self::Generic {
                 ref mut pass_fields,
+            }
+            | Self::StoreCard {
+                ref mut pass_fields,
             } => pass_fields.header_fields.push(field),
         }
         self
@@ -357,6 +876,9 @@ impl Type {
             }
             | Self::Generic {
                 ref mut pass_fields,
+            }
+            | Self::StoreCard {
+                ref mut pass_fields,
             } => pass_fields.primary_fields.push(field),
         }
         self
@@ -377,6 +899,9 @@ impl Type {
             }
             | Self::Generic {
                 ref mut pass_fields,
+            }
+            | Self::StoreCard {
+                ref mut pass_fields,
             } => pass_fields.secondary_fields.push(field),
         }
         self
@@ -385,6 +910,8 @@ impl Type {
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeZone;
+
     use crate::pass::semantic_tags::SemanticTagSeat;
 
     use super::*;
@@ -582,4 +1109,318 @@ mod tests {
         let json = serde_json::to_string_pretty(&event_ticket).unwrap();
         assert_eq!(json_expected, json);
     }
+
+    #[test]
+    fn make_store_card() {
+        // Serialization test
+        let store_card = Type::StoreCard {
+            pass_fields: Fields {
+                ..Default::default()
+            },
+        }
+        .add_header_field(Content::new("tier", "Gold", Default::default()))
+        .add_primary_field(Content::new(
+            "balance",
+            "1200",
+            ContentOptions {
+                label: String::from("POINTS").into(),
+                ..Default::default()
+            },
+        ))
+        .add_secondary_field(Content::new("member", "John Doe", Default::default()))
+        .add_auxiliary_field(Content::new("since", "2020", Default::default()))
+        .add_back_field(Content::new("terms", "No terms", Default::default()));
+
+        let json = serde_json::to_string_pretty(&store_card).unwrap();
+
+        println!("{}", json);
+
+        let json_expected = r#"{
+  "storeCard": {
+    "auxiliaryFields": [
+      {
+        "key": "since",
+        "value": "2020"
+      }
+    ],
+    "backFields": [
+      {
+        "key": "terms",
+        "value": "No terms"
+      }
+    ],
+    "headerFields": [
+      {
+        "key": "tier",
+        "value": "Gold"
+      }
+    ],
+    "primaryFields": [
+      {
+        "key": "balance",
+        "value": "1200",
+        "label": "POINTS"
+      }
+    ],
+    "secondaryFields": [
+      {
+        "key": "member",
+        "value": "John Doe"
+      }
+    ]
+  }
+}"#;
+        assert_eq!(json_expected, json);
+
+        // Deserialization test
+        let store_card: Type = serde_json::from_str(json_expected).unwrap();
+        let json = serde_json::to_string_pretty(&store_card).unwrap();
+        assert_eq!(json_expected, json);
+    }
+
+    #[test]
+    fn parse_typed_values() {
+        let json = r#"{
+  "storeCard": {
+    "auxiliaryFields": [
+      {
+        "key": "expires",
+        "value": "2013-04-24T10:00-05:00",
+        "dateStyle": "PKDateStyleShort"
+      },
+      {
+        "key": "updated",
+        "value": "2013-04-24T10:00:00Z",
+        "timeStyle": "PKDateStyleShort"
+      }
+    ],
+    "backFields": [
+      {
+        "key": "terms",
+        "value": "2013-04-24T10:00:00Z"
+      }
+    ],
+    "headerFields": [
+      {
+        "key": "points",
+        "value": 1200,
+        "numberStyle": "PKNumberStyleDecimal"
+      }
+    ],
+    "primaryFields": [
+      {
+        "key": "balance",
+        "value": 21.75,
+        "currencyCode": "USD"
+      }
+    ],
+    "secondaryFields": []
+  }
+}"#;
+        let store_card: Type = serde_json::from_str(json).unwrap();
+
+        let date = FixedOffset::west_opt(5 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2013, 4, 24, 10, 0, 0)
+            .unwrap();
+        assert_eq!(
+            FieldValue::Date(date),
+            store_card.field("expires").unwrap().value
+        );
+        assert!(matches!(
+            store_card.field("updated").unwrap().value,
+            FieldValue::Date(_)
+        ));
+        assert_eq!(
+            FieldValue::Number(1200.0),
+            store_card.field("points").unwrap().value
+        );
+        assert_eq!(
+            FieldValue::Currency(21.75),
+            store_card.field("balance").unwrap().value
+        );
+
+        // Date without date or time style is a text
+        assert_eq!(
+            FieldValue::from("2013-04-24T10:00:00Z"),
+            store_card.field("terms").unwrap().value
+        );
+
+        // Numbers are kept as numbers
+        let json = serde_json::to_value(&store_card).unwrap();
+        assert_eq!(1200, json["storeCard"]["headerFields"][0]["value"]);
+        assert_eq!(21.75, json["storeCard"]["primaryFields"][0]["value"]);
+        assert_eq!(
+            "2013-04-24T10:00:00-05:00",
+            json["storeCard"]["auxiliaryFields"][0]["value"]
+        );
+    }
+
+    #[test]
+    fn make_typed_fields() {
+        let field = Content::new_number(
+            "points",
+            0.25,
+            NumberStyle::Percent,
+            ContentOptions {
+                currency_code: Some(String::from("USD")),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            r#"{"key":"points","value":0.25,"numberStyle":"PKNumberStylePercent"}"#,
+            serde_json::to_string(&field).unwrap()
+        );
+
+        let field = Content::new_currency(
+            "balance",
+            100.0,
+            "EUR",
+            ContentOptions {
+                number_style: Some(NumberStyle::Decimal),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            r#"{"key":"balance","value":100,"currencyCode":"EUR"}"#,
+            serde_json::to_string(&field).unwrap()
+        );
+
+        // NaN and infinity are not written as null
+        let field =
+            Content::new_number("points", f64::NAN, NumberStyle::Decimal, Default::default());
+        assert!(serde_json::to_string(&field).is_err());
+        let field = Content::new_currency("balance", f64::INFINITY, "EUR", Default::default());
+        assert!(serde_json::to_string(&field).is_err());
+    }
+
+    #[test]
+    fn update_fields_by_key() {
+        let mut fields = Type::BoardingPass {
+            pass_fields: Default::default(),
+            transit_type: TransitType::Train,
+        }
+        .add_header_field(Content::new("gate", "4", Default::default()))
+        .add_primary_field(Content::new("from", "Moscow", Default::default()))
+        .add_primary_field(Content::new("to", "Vladivostok", Default::default()))
+        .add_back_field(Content::new("terms", "Terms", Default::default()));
+
+        assert!(fields.set_value("gate", "5"));
+        assert_eq!(FieldValue::from("5"), fields.field("gate").unwrap().value);
+        assert!(!fields.set_value("seat", "12"));
+        assert!(fields.field("seat").is_none());
+
+        fields.field_mut("to").unwrap().options.label = Some(String::from("To"));
+        assert_eq!(
+            Some("To"),
+            fields.field("to").unwrap().options.label.as_deref()
+        );
+
+        // Move to another group
+        assert!(fields.move_field("gate", FieldGroup::Secondary));
+        assert!(fields.pass_fields().header_fields.is_empty());
+        assert_eq!("gate", fields.pass_fields().secondary_fields[0].key);
+        assert!(!fields.move_field("seat", FieldGroup::Back));
+
+        // Remove
+        let removed = fields.remove_field("terms").unwrap();
+        assert_eq!(FieldValue::from("Terms"), removed.value);
+        assert!(fields.remove_field("terms").is_none());
+
+        // Every field, front fields first
+        for (_, field) in fields.iter_mut() {
+            field.value = field.value.to_string().to_uppercase().into();
+        }
+        let values: Vec<_> = fields
+            .iter()
+            .map(|(group, field)| (group, field.value.to_string()))
+            .collect();
+        assert_eq!(
+            vec![
+                (FieldGroup::Primary, String::from("MOSCOW")),
+                (FieldGroup::Primary, String::from("VLADIVOSTOK")),
+                (FieldGroup::Secondary, String::from("5")),
+            ],
+            values
+        );
+    }
+
+    #[test]
+    fn hidden_fields() {
+        let add_fields = |mut fields: Type, count: usize| {
+            for i in 0..count {
+                fields = fields
+                    .add_header_field(Content::new(&format!("h{}", i), "", Default::default()))
+                    .add_primary_field(Content::new(&format!("p{}", i), "", Default::default()))
+                    .add_secondary_field(Content::new(&format!("s{}", i), "", Default::default()))
+                    .add_auxiliary_field(Content::new(&format!("a{}", i), "", Default::default()))
+                    .add_back_field(Content::new(&format!("b{}", i), "", Default::default()));
+            }
+            fields
+        };
+        let hidden_keys = |fields: &Type, images: &ImageSet, square_barcode: bool| {
+            fields
+                .hidden_fields(images, square_barcode)
+                .into_iter()
+                .map(|(_, field)| field.key.clone())
+                .collect::<Vec<String>>()
+        };
+
+        let boarding_pass = add_fields(
+            Type::BoardingPass {
+                pass_fields: Default::default(),
+                transit_type: TransitType::Train,
+            },
+            6,
+        );
+        assert_eq!(
+            vec!["h3", "h4", "h5", "p2", "p3", "p4", "p5", "s5", "a5"],
+            hidden_keys(&boarding_pass, &ImageSet::default(), true)
+        );
+
+        let coupon = add_fields(
+            Type::Coupon {
+                pass_fields: Default::default(),
+            },
+            3,
+        );
+        assert_eq!(
+            vec!["p1", "p2", "a1", "a2"],
+            hidden_keys(&coupon, &ImageSet::default(), false)
+        );
+
+        let event_ticket = add_fields(
+            Type::EventTicket {
+                pass_fields: Default::default(),
+            },
+            3,
+        );
+        assert_eq!(
+            vec!["p1", "p2"],
+            hidden_keys(&event_ticket, &ImageSet::default(), false)
+        );
+        let strip = ImageSet {
+            strip: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            vec!["p1", "p2", "a1", "a2"],
+            hidden_keys(&event_ticket, &strip, false)
+        );
+
+        let generic = add_fields(
+            Type::Generic {
+                pass_fields: Default::default(),
+            },
+            5,
+        );
+        assert_eq!(
+            vec!["h3", "h4", "p1", "p2", "p3", "p4", "s4", "a4"],
+            hidden_keys(&generic, &ImageSet::default(), false)
+        );
+        assert_eq!(
+            vec!["h3", "h4", "p1", "p2", "p3", "p4", "s4", "a0", "a1", "a2", "a3", "a4"],
+            hidden_keys(&generic, &ImageSet::default(), true)
+        );
+    }
 }
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Represents a date or date interval, when the pass is relevant (iOS 18).
+///
+/// Set either [date](RelevantDate::date), or both [start_date](RelevantDate::start_date) and [end_date](RelevantDate::end_date).
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RelevantDate {
+    /// The date and time when the pass becomes relevant.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "super::date_format")]
+    pub date: Option<DateTime<Utc>>,
+
+    /// The date and time for the pass relevancy interval to begin.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "super::date_format")]
+    pub start_date: Option<DateTime<Utc>>,
+
+    /// The date and time for the pass relevancy interval to end.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "super::date_format")]
+    pub end_date: Option<DateTime<Utc>>,
+}
+
+impl RelevantDate {
+    /// Creates `RelevantDate` for a single date.
+    pub fn date(date: DateTime<Utc>) -> Self {
+        Self {
+            date: Some(date),
+            ..Default::default()
+        }
+    }
+
+    /// Creates `RelevantDate` for interval between `start` and `end`.
+    pub fn interval(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            start_date: Some(start),
+            end_date: Some(end),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn make_relevant_dates() {
+        let start = Utc.with_ymd_and_hms(2024, 9, 1, 18, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 9, 1, 23, 0, 0).unwrap();
+        let dates = vec![
+            RelevantDate::date(start),
+            RelevantDate::interval(start, end),
+        ];
+
+        let json = serde_json::to_string_pretty(&dates).unwrap();
+
+        let json_expected = r#"[
+  {
+    "date": "2024-09-01T18:00:00+00:00"
+  },
+  {
+    "startDate": "2024-09-01T18:00:00+00:00",
+    "endDate": "2024-09-01T23:00:00+00:00"
+  }
+]"#;
+        assert_eq!(json_expected, json);
+
+        // Deserialization test
+        let dates: Vec<RelevantDate> = serde_json::from_str(json_expected).unwrap();
+        let json = serde_json::to_string_pretty(&dates).unwrap();
+        assert_eq!(json_expected, json);
+    }
+}
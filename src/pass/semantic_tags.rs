@@ -2,17 +2,49 @@ use chrono::{DateTime, Utc};
 use is_empty::IsEmpty;
 use serde::{Deserialize, Serialize};
 
+use super::visual_appearance::Color;
+
 /// Machine-readable metadata the system uses to offer a pass and suggest related actions.
 ///
 /// <https://developer.apple.com/documentation/walletpasses/semantictags>
 #[derive(Serialize, Deserialize, Debug, IsEmpty)]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTags {
+    /// Additional ticket attributes that other tags or keys in the pass don’t include.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_ticket_attributes: Option<String>,
+
+    /// The admission level for the ticket, such as “VIP”.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub admission_level: Option<String>,
+
+    /// An abbreviation of the admission level, such as “VIP”.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub admission_level_abbreviation: Option<String>,
+
     /// The IATA airline code, such as “EX” for flightCode “EX123”. Use this key only for airline boarding passes.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub airline_code: Option<String>,
 
+    /// An array of the Apple Music persistent ID for each album corresponding to the event, in decreasing order of significance.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(rename = "albumIDs")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[is_empty(if = "Vec::is_empty")]
+    pub album_ids: Vec<String>,
+
     /// An array of the Apple Music persistent ID for each artist performing at the event, in decreasing order of significance.
     ///
     /// Use this key for any type of event ticket.
@@ -22,6 +54,13 @@ pub struct SemanticTags {
     #[is_empty(if = "Vec::is_empty")]
     pub artist_ids: Vec<String>,
 
+    /// The name of the person the ticket grants admission to.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attendee_name: Option<String>,
+
     /// The unique abbreviation of the away team’s name. Use this key only for a sports event ticket.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -221,12 +260,26 @@ pub struct SemanticTags {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration: Option<u32>,
 
+    /// The description of the entrance, such as “Enter through the south gate”.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entrance_description: Option<String>,
+
     /// The date and time the event ends. Use this key for any type of event ticket.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(with = "super::date_format")]
     pub event_end_date: Option<DateTime<Utc>>,
 
+    /// A message that the system displays on the pass while the event is live, such as “Show your ticket at the entrance”.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_live_message: Option<String>,
+
     /// The full name of the event, such as the title of a movie.
     ///
     /// Use this key for any type of event ticket.
@@ -242,6 +295,13 @@ pub struct SemanticTags {
     #[serde(with = "super::date_format")]
     pub event_start_date: Option<DateTime<Utc>>,
 
+    /// Details of the event start date, e.g. when the start time is not announced yet.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_start_date_info: Option<SemanticTagEventDateInfo>,
+
     /// The type of event. Use this key for any type of event ticket.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -330,6 +390,15 @@ pub struct SemanticTags {
     #[is_empty(if = "Vec::is_empty")]
     pub performer_names: Vec<String>,
 
+    /// An array of the Apple Music persistent ID for each playlist corresponding to the event, in decreasing order of significance.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(rename = "playlistIDs")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[is_empty(if = "Vec::is_empty")]
+    pub playlist_ids: Vec<String>,
+
     /// The priority status the ticketed passenger holds, such as “Gold” or “Silver”.
     ///
     /// Use this key for any type of boarding pass.
@@ -366,6 +435,13 @@ pub struct SemanticTags {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sport_name: Option<String>,
 
+    /// A Boolean value that indicates whether tailgating is allowed at the event.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tailgating_allowed: Option<bool>,
+
     /// The total price for the pass. Use this key for any pass type.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -411,6 +487,30 @@ pub struct SemanticTags {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vehicle_type: Option<String>,
 
+    /// The date when the box office of the venue opens.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "super::date_format")]
+    pub venue_box_office_open_date: Option<DateTime<Utc>>,
+
+    /// The date when the venue closes.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "super::date_format")]
+    pub venue_close_date: Option<DateTime<Utc>>,
+
+    /// The date when the doors of the venue open.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "super::date_format")]
+    pub venue_doors_open_date: Option<DateTime<Utc>>,
+
     /// The full name of the entrance, such as “Gate A”, to use to gain access to the ticketed event.
     ///
     /// Use this key for any type of event ticket.
@@ -418,6 +518,22 @@ pub struct SemanticTags {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub venue_entrance: Option<String>,
 
+    /// The date when the fan zone of the venue opens.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "super::date_format")]
+    pub venue_fan_zone_open_date: Option<DateTime<Utc>>,
+
+    /// The date when the gates of the venue open.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "super::date_format")]
+    pub venue_gates_open_date: Option<DateTime<Utc>>,
+
     /// An object that represents the geographic coordinates of the venue.
     ///
     /// Use this key for any type of event ticket.
@@ -432,6 +548,22 @@ pub struct SemanticTags {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub venue_name: Option<String>,
 
+    /// The date when the venue opens.
+    ///
+    /// Use this key for any type of event ticket, if none of more specific open dates applies.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "super::date_format")]
+    pub venue_open_date: Option<DateTime<Utc>>,
+
+    /// The date when the parking lots of the venue open.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "super::date_format")]
+    pub venue_parking_lots_open_date: Option<DateTime<Utc>>,
+
     /// The phone number for enquiries about the venue’s ticketed event.
     ///
     /// Use this key for any type of event ticket.
@@ -439,6 +571,13 @@ pub struct SemanticTags {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub venue_phone_number: Option<String>,
 
+    /// The full name of the region of the venue, such as “West Stand”.
+    ///
+    /// Use this key for any type of event ticket.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub venue_region_name: Option<String>,
+
     /// The full name of the room where the ticketed event is to take place.
     ///
     /// Use this key for any type of event ticket.
@@ -455,6 +594,48 @@ pub struct SemanticTags {
     pub wifi_access: Vec<SemanticTagWifiNetwork>,
 }
 
+/// Details of the event start date.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTagEventDateInfo {
+    /// (Required) The date and time the event starts.
+    #[serde(with = "super::date_format::required")]
+    pub date: DateTime<Utc>,
+
+    /// A Boolean value that indicates whether the system ignores the time components of the date.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_time_components: Option<bool>,
+
+    /// The time zone of the event, such as “America/Los_Angeles”.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+
+    /// A Boolean value that indicates whether the start date is not announced yet.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unannounced: Option<bool>,
+
+    /// A Boolean value that indicates whether the start date is not determined yet.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub undetermined: Option<bool>,
+}
+
+impl SemanticTagEventDateInfo {
+    /// Creates `SemanticTagEventDateInfo` with date.
+    pub fn new(date: DateTime<Utc>) -> Self {
+        Self {
+            date,
+            ignore_time_components: None,
+            time_zone: None,
+            unannounced: None,
+            undetermined: None,
+        }
+    }
+}
+
 /// Represents an amount of money and type of currency.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -550,6 +731,11 @@ impl Default for SemanticTagPersonNameComponents {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticTagSeat {
+    /// The aisle that contains the seat.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat_aisle: Option<String>,
+
     /// A description of the seat, such as “A flat bed seat”.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -560,6 +746,11 @@ pub struct SemanticTagSeat {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seat_identifier: Option<String>,
 
+    /// The level that contains the seat.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat_level: Option<String>,
+
     /// The number of the seat.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -575,6 +766,11 @@ pub struct SemanticTagSeat {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seat_section: Option<String>,
 
+    /// A color associated with the section of the seat.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat_section_color: Option<Color>,
+
     /// The type of seat, such as “Reserved seating”.
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -585,11 +781,14 @@ impl Default for SemanticTagSeat {
     /// Creates an empty `SemanticTagSeat`.
     fn default() -> Self {
         Self {
+            seat_aisle: None,
             seat_description: None,
             seat_identifier: None,
+            seat_level: None,
             seat_number: None,
             seat_row: None,
             seat_section: None,
+            seat_section_color: None,
             seat_type: None,
         }
     }
@@ -631,8 +830,13 @@ impl Default for SemanticTags {
     /// Creates an empty `SemanticTags`.
     fn default() -> Self {
         Self {
+            additional_ticket_attributes: None,
+            admission_level: None,
+            admission_level_abbreviation: None,
             airline_code: None,
+            album_ids: Vec::new(),
             artist_ids: Vec::new(),
+            attendee_name: None,
             away_team_abbreviation: None,
             away_team_location: None,
             away_team_name: None,
@@ -661,9 +865,12 @@ impl Default for SemanticTags {
             destination_station_name: None,
             destination_terminal: None,
             duration: None,
+            entrance_description: None,
             event_end_date: None,
+            event_live_message: None,
             event_name: None,
             event_start_date: None,
+            event_start_date_info: None,
             event_type: None,
             flight_code: None,
             flight_number: None,
@@ -680,11 +887,13 @@ impl Default for SemanticTags {
             original_departure_date: None,
             passenger_name: None,
             performer_names: Vec::new(),
+            playlist_ids: Vec::new(),
             priority_status: None,
             seats: Vec::new(),
             security_screening: None,
             silence_requested: None,
             sport_name: None,
+            tailgating_allowed: None,
             total_price: None,
             transit_provider: None,
             transit_status: None,
@@ -692,10 +901,18 @@ impl Default for SemanticTags {
             vehicle_name: None,
             vehicle_number: None,
             vehicle_type: None,
+            venue_box_office_open_date: None,
+            venue_close_date: None,
+            venue_doors_open_date: None,
             venue_entrance: None,
+            venue_fan_zone_open_date: None,
+            venue_gates_open_date: None,
             venue_location: None,
             venue_name: None,
+            venue_open_date: None,
+            venue_parking_lots_open_date: None,
             venue_phone_number: None,
+            venue_region_name: None,
             venue_room: None,
             wifi_access: Vec::new(),
         }
@@ -703,6 +920,7 @@ impl Default for SemanticTags {
 }
 
 #[cfg(test)]
+#[allow(clippy::zero_prefixed_literal)]
 mod tests {
     use chrono::prelude::*;
 
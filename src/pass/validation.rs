@@ -0,0 +1,434 @@
+use std::{collections::HashSet, fmt};
+
+use super::{
+    fields::{self, FieldGroup},
+    Pass,
+};
+
+/// Maximum number of [locations](Pass::locations) used by Wallet
+pub const MAX_LOCATIONS: usize = 10;
+
+/// Maximum size of [NFC message](super::nfc::NFC::message) in bytes
+pub const MAX_NFC_MESSAGE_LENGTH: usize = 64;
+
+/// How serious is the [Diagnostic]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Wallet rejects the pass
+    Error,
+    /// Pass is accepted, but some content is ignored or truncated
+    Warning,
+}
+
+/// Problem found by [Pass::validate](crate::Pass::validate) or [Package::validate](crate::Package::validate)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// `transitType` is set in pass.json of other style than boarding pass
+    TransitTypeNotAllowed {
+        /// Style of the pass, e.g. `eventTicket`
+        style: String,
+    },
+
+    /// `textAlignment` is set on primary or back field
+    TextAlignmentNotAllowed {
+        /// Group of the field
+        group: FieldGroup,
+        /// Key of the field
+        key: String,
+    },
+
+    /// Field key is used more than once (across all field groups)
+    DuplicateFieldKey {
+        /// Group of the repeated field
+        group: FieldGroup,
+        /// Repeated key
+        key: String,
+    },
+
+    /// `changeMessage` doesn't contain `%@` placeholder for the new value
+    ChangeMessageWithoutPlaceholder {
+        /// Key of the field
+        key: String,
+    },
+
+    /// `currencyCode` is not an ISO 4217 currency code
+    InvalidCurrencyCode {
+        /// Key of the field
+        key: String,
+        /// Invalid code
+        code: String,
+    },
+
+    /// More than [MAX_LOCATIONS] locations
+    TooManyLocations {
+        /// Number of locations in pass
+        count: usize,
+    },
+
+    /// NFC message is longer than [MAX_NFC_MESSAGE_LENGTH] bytes
+    NfcMessageTooLong {
+        /// Message length in bytes
+        length: usize,
+    },
+
+    /// Package doesn't contain required icon.png
+    MissingIcon,
+
+    /// Field is not displayed for the pass style and images, see [Pass::hidden_fields](crate::Pass::hidden_fields)
+    HiddenField {
+        /// Group of the field
+        group: FieldGroup,
+        /// Key of the field
+        key: String,
+    },
+
+    /// File of package can't be parsed, e.g. malformed manifest.json. Found on [reading](crate::Package::read) package.
+    InvalidFile {
+        /// Path of file in package
+        file: String,
+        /// Parsing error
+        message: String,
+    },
+
+    /// Image is larger than allotted space of its [type](crate::resource::Type::allotted_pixels)
+    ImageTooLarge {
+        /// File name of image, e.g. `strip@2x.png`
+        file: String,
+        /// Image size in pixels
+        size: (u32, u32),
+        /// Allotted space in pixels
+        allotted: (u32, u32),
+    },
+
+    /// Icon or thumbnail is smaller than its [size](crate::resource::Type::allotted_pixels), e.g. @3x image at 1x resolution
+    ImageTooSmall {
+        /// File name of image, e.g. `icon@2x.png`
+        file: String,
+        /// Image size in pixels
+        size: (u32, u32),
+        /// Expected size in pixels
+        expected: (u32, u32),
+    },
+}
+
+impl Diagnostic {
+    /// Severity of the problem
+    pub fn severity(&self) -> Severity {
+        match self {
+            Diagnostic::NfcMessageTooLong { .. }
+            | Diagnostic::TooManyLocations { .. }
+            | Diagnostic::HiddenField { .. }
+            | Diagnostic::ImageTooLarge { .. }
+            | Diagnostic::ImageTooSmall { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Check that Wallet rejects the pass with this problem
+    pub fn is_error(&self) -> bool {
+        self.severity() == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::TransitTypeNotAllowed { style } => {
+                write!(f, "transitType is not allowed in {} pass", style)
+            }
+            Diagnostic::TextAlignmentNotAllowed { group, key } => {
+                write!(f, "textAlignment is not allowed in {} ({})", group, key)
+            }
+            Diagnostic::DuplicateFieldKey { group, key } => {
+                write!(f, "field key {} is repeated in {}", key, group)
+            }
+            Diagnostic::ChangeMessageWithoutPlaceholder { key } => {
+                write!(f, "changeMessage of {} doesn't contain %@", key)
+            }
+            Diagnostic::InvalidCurrencyCode { key, code } => {
+                write!(f, "currencyCode {} of {} is not ISO 4217 code", code, key)
+            }
+            Diagnostic::TooManyLocations { count } => write!(
+                f,
+                "{} locations, only first {} are used",
+                count, MAX_LOCATIONS
+            ),
+            Diagnostic::NfcMessageTooLong { length } => write!(
+                f,
+                "NFC message is {} bytes, maximum is {}",
+                length, MAX_NFC_MESSAGE_LENGTH
+            ),
+            Diagnostic::MissingIcon => write!(f, "icon.png is missed in package"),
+            Diagnostic::HiddenField { group, key } => {
+                write!(f, "field {} in {} is not displayed on pass", key, group)
+            }
+            Diagnostic::InvalidFile { file, message } => {
+                write!(f, "invalid {}: {}", file, message)
+            }
+            Diagnostic::ImageTooLarge {
+                file,
+                size,
+                allotted,
+            } => write!(
+                f,
+                "{} is {}x{} pixels, allotted space is {}x{}",
+                file, size.0, size.1, allotted.0, allotted.1
+            ),
+            Diagnostic::ImageTooSmall {
+                file,
+                size,
+                expected,
+            } => write!(
+                f,
+                "{} is {}x{} pixels, expected size is {}x{}",
+                file, size.0, size.1, expected.0, expected.1
+            ),
+        }
+    }
+}
+
+/// Check pass.json content
+pub(crate) fn validate_pass(pass: &Pass) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    if pass.fields.pass_fields().stray_transit_type {
+        let style = match pass.fields {
+            fields::Type::BoardingPass { .. } => "boardingPass",
+            fields::Type::Coupon { .. } => "coupon",
+            fields::Type::EventTicket { .. } => "eventTicket",
+            fields::Type::Generic { .. } => "generic",
+            fields::Type::StoreCard { .. } => "storeCard",
+        };
+        diagnostics.push(Diagnostic::TransitTypeNotAllowed {
+            style: String::from(style),
+        });
+    }
+
+    let mut keys = HashSet::new();
+    for (group, field) in pass.fields.pass_fields().iter() {
+        if !keys.insert(field.key.as_str()) {
+            diagnostics.push(Diagnostic::DuplicateFieldKey {
+                group,
+                key: field.key.clone(),
+            });
+        }
+
+        if field.options.text_alignment.is_some()
+            && matches!(group, FieldGroup::Primary | FieldGroup::Back)
+        {
+            diagnostics.push(Diagnostic::TextAlignmentNotAllowed {
+                group,
+                key: field.key.clone(),
+            });
+        }
+
+        if let Some(change_message) = &field.options.change_message {
+            if !change_message.contains("%@") {
+                diagnostics.push(Diagnostic::ChangeMessageWithoutPlaceholder {
+                    key: field.key.clone(),
+                });
+            }
+        }
+
+        if let Some(code) = &field.options.currency_code {
+            if !is_currency_code(code) {
+                diagnostics.push(Diagnostic::InvalidCurrencyCode {
+                    key: field.key.clone(),
+                    code: code.clone(),
+                });
+            }
+        }
+    }
+
+    if pass.locations.len() > MAX_LOCATIONS {
+        diagnostics.push(Diagnostic::TooManyLocations {
+            count: pass.locations.len(),
+        });
+    }
+
+    if let Some(nfc) = &pass.nfc {
+        if nfc.message.len() > MAX_NFC_MESSAGE_LENGTH {
+            diagnostics.push(Diagnostic::NfcMessageTooLong {
+                length: nfc.message.len(),
+            });
+        }
+    }
+
+    diagnostics
+}
+
+/// Check that code is an active ISO 4217 currency code
+pub fn is_currency_code(code: &str) -> bool {
+    CURRENCY_CODES.binary_search(&code).is_ok()
+}
+
+/// Active ISO 4217 currency codes (sorted)
+const CURRENCY_CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUC", "CUP",
+    "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP",
+    "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS",
+    "INR", "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW",
+    "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD",
+    "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN",
+    "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR",
+    "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SLL",
+    "SOS", "SRD", "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY",
+    "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES",
+    "VND", "VUV", "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR",
+    "XOF", "XPD", "XPF", "XPT", "XSU", "XTS", "XUA", "XXX", "YER", "ZAR", "ZMW", "ZWG", "ZWL",
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::pass::{location::Location, nfc::NFC, PassBuilder, PassConfig};
+
+    use super::*;
+
+    fn make_builder() -> PassBuilder {
+        PassBuilder::new(PassConfig {
+            organization_name: String::from("Apple inc."),
+            description: String::from("Example pass"),
+            pass_type_identifier: String::from("com.example.pass"),
+            team_identifier: String::from("AA00AA0A0A"),
+            serial_number: String::from("ABCDEFG1234567890"),
+        })
+    }
+
+    #[test]
+    fn currency_codes_sorted() {
+        assert!(CURRENCY_CODES.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(is_currency_code("USD"));
+        assert!(!is_currency_code("usd"));
+        assert!(!is_currency_code("ABC"));
+    }
+
+    #[test]
+    fn validate_valid_pass() {
+        let pass =
+            make_builder()
+                .fields(
+                    fields::Type::Generic {
+                        pass_fields: Default::default(),
+                    }
+                    .add_header_field(fields::Content::new(
+                        "balance",
+                        "100",
+                        fields::ContentOptions {
+                            currency_code: String::from("EUR").into(),
+                            change_message: String::from("Balance changed to %@").into(),
+                            text_alignment: fields::TextAlignment::Right.into(),
+                            ..Default::default()
+                        },
+                    ))
+                    .add_primary_field(fields::Content::new("name", "Pass", Default::default())),
+                )
+                .build();
+
+        assert_eq!(Vec::<Diagnostic>::new(), pass.validate());
+    }
+
+    #[test]
+    fn validate_invalid_pass() {
+        let mut builder = make_builder()
+            .fields(
+                fields::Type::Generic {
+                    pass_fields: Default::default(),
+                }
+                .add_primary_field(fields::Content::new(
+                    "balance",
+                    "100",
+                    fields::ContentOptions {
+                        currency_code: String::from("EURO").into(),
+                        text_alignment: fields::TextAlignment::Right.into(),
+                        ..Default::default()
+                    },
+                ))
+                .add_back_field(fields::Content::new(
+                    "balance",
+                    "100",
+                    fields::ContentOptions {
+                        change_message: String::from("Balance changed").into(),
+                        ..Default::default()
+                    },
+                )),
+            )
+            .nfc(NFC {
+                message: "x".repeat(65),
+                ..Default::default()
+            });
+        for _ in 0..11 {
+            builder = builder.add_location(Location {
+                latitude: 0.0,
+                longitude: 0.0,
+                ..Default::default()
+            });
+        }
+
+        let diagnostics = builder.build().validate();
+
+        assert_eq!(
+            vec![
+                Diagnostic::TextAlignmentNotAllowed {
+                    group: FieldGroup::Primary,
+                    key: String::from("balance"),
+                },
+                Diagnostic::InvalidCurrencyCode {
+                    key: String::from("balance"),
+                    code: String::from("EURO"),
+                },
+                Diagnostic::DuplicateFieldKey {
+                    group: FieldGroup::Back,
+                    key: String::from("balance"),
+                },
+                Diagnostic::ChangeMessageWithoutPlaceholder {
+                    key: String::from("balance"),
+                },
+                Diagnostic::TooManyLocations { count: 11 },
+                Diagnostic::NfcMessageTooLong { length: 65 },
+            ],
+            diagnostics
+        );
+        assert_eq!(
+            "textAlignment is not allowed in primaryFields (balance)",
+            diagnostics[0].to_string()
+        );
+        assert!(!diagnostics[4].is_error());
+    }
+
+    #[test]
+    fn validate_transit_type() {
+        let json = r#"{
+  "formatVersion": 1,
+  "organizationName": "Apple inc.",
+  "description": "Example pass",
+  "passTypeIdentifier": "com.example.pass",
+  "teamIdentifier": "AA00AA0A0A",
+  "serialNumber": "ABCDEFG1234567890",
+  "eventTicket": {
+    "transitType": "PKTransitTypeTrain",
+    "headerFields": [],
+    "primaryFields": [],
+    "secondaryFields": [],
+    "auxiliaryFields": [],
+    "backFields": []
+  }
+}"#;
+        let pass = Pass::from_json(json).unwrap();
+        assert!(!pass.make_json().unwrap().contains("transitType"));
+
+        let diagnostics = pass.validate();
+        assert_eq!(
+            vec![Diagnostic::TransitTypeNotAllowed {
+                style: String::from("eventTicket")
+            }],
+            diagnostics
+        );
+        assert!(diagnostics[0].is_error());
+
+        let pass = Pass::from_json(&json.replace("eventTicket", "boardingPass")).unwrap();
+        assert!(pass.make_json().unwrap().contains("transitType"));
+        assert!(pass.validate().is_empty());
+    }
+}
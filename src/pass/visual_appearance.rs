@@ -1,3 +1,5 @@
+use std::fmt;
+
 use regex::Regex;
 use serde::{de, Deserialize, Serialize};
 
@@ -21,6 +23,11 @@ pub struct VisualAppearance {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub background_color: Option<Color>,
+
+    /// A background color for the footer of poster event ticket (iOS 18)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer_background_color: Option<Color>,
 }
 
 impl Default for VisualAppearance {
@@ -30,10 +37,25 @@ impl Default for VisualAppearance {
             label_color: None,
             foreground_color: None,
             background_color: None,
+            footer_background_color: None,
         }
     }
 }
 
+/// Style of pass, that Wallet uses if device supports it.
+///
+/// Listed in [preferred_style_schemes](crate::Pass::preferred_style_schemes) in order of preference,
+/// a pass with poster style must contain fields for the fallback style.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StyleScheme {
+    /// Poster event ticket with artwork (iOS 18)
+    PosterEventTicket,
+
+    /// Regular event ticket
+    EventTicket,
+}
+
 /// Represents color - specified as a CSS-style RGB triple
 #[derive(Debug)]
 pub struct Color {
@@ -68,14 +90,20 @@ impl Color {
     }
 }
 
+impl fmt::Display for Color {
+    /// Format `Color` as CSS `rgb(red, green, blue)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rgb({}, {}, {})", self.r, self.g, self.b)
+    }
+}
+
 impl Serialize for Color {
     /// Serialize `Color` to format `rgb(red, blue, green)`
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let str = format!("rgb({}, {}, {})", self.r, self.g, self.b);
-        serializer.serialize_str(&str)
+        serializer.serialize_str(&self.to_string())
     }
 }
 
@@ -118,6 +146,7 @@ mod tests {
             label_color: Color::new(255, 100, 100),
             foreground_color: Color::new(255, 100, 100),
             background_color: Color::new(255, 100, 100),
+            ..Default::default()
         };
 
         let json = serde_json::to_string_pretty(&appearance).unwrap();
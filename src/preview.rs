@@ -0,0 +1,654 @@
+//! Offline preview of a pass, approximate to Wallet layout.
+//!
+//! [Preview] draws front and back of a [Package] as SVG images and combines them into HTML page.
+//! Layout follows the pass style: header with logo, primary fields (with strip or thumbnail image),
+//! secondary and auxiliary rows and the first barcode. Fields, hidden by Wallet, are not drawn.
+//!
+//! Barcodes are drawn with `barcode` feature, otherwise as placeholders.
+//! With `preview-png` feature sides can be rasterized to PNG.
+use std::fmt::Write;
+
+use crate::{
+    barcode::{Barcode, BarcodeFormat},
+    fields::{Content, FieldGroup, TransitType, Type},
+    resource::{self, Resource, Version},
+    Package,
+};
+
+#[cfg(feature = "preview-png")]
+use crate::Error;
+
+/// Width of pass in points
+const WIDTH: u32 = 320;
+
+/// Space between pass edge and content
+const PADDING: u32 = 12;
+
+/// Fonts for all text
+const FONT: &str = "-apple-system, Helvetica, Arial, sans-serif";
+
+/// Preview of pass front and back.
+///
+/// ```
+/// use passes::{preview::Preview, Package, PassBuilder, PassConfig};
+///
+/// let pass = PassBuilder::new(PassConfig {
+///     organization_name: String::from("Apple inc."),
+///     description: String::from("Example pass"),
+///     pass_type_identifier: String::from("com.example.pass"),
+///     team_identifier: String::from("AA00AA0A0A"),
+///     serial_number: String::from("ABCDEFG1234567890"),
+/// })
+/// .logo_text(String::from("Example"))
+/// .build();
+///
+/// let preview = Preview::new(&Package::new(pass));
+/// let html = preview.html();
+/// assert!(html.contains("Example"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Preview {
+    /// Front of pass (SVG)
+    pub front: String,
+
+    /// Back of pass (SVG)
+    pub back: String,
+
+    /// Title of HTML page
+    title: String,
+}
+
+impl Preview {
+    /// Draw preview of package
+    pub fn new(package: &Package) -> Self {
+        let colors = Colors::new(package);
+        Self {
+            front: front(package, &colors),
+            back: back(package),
+            title: package.pass.config.description.clone(),
+        }
+    }
+
+    /// HTML page with front and back of pass side by side
+    pub fn html(&self) -> String {
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<style>
+body {{ margin: 0; padding: 24px; display: flex; flex-wrap: wrap; gap: 24px; align-items: flex-start; background: #e5e5ea; font-family: {}; }}
+figure {{ margin: 0; }}
+figure svg {{ display: block; border-radius: 12px; box-shadow: 0 4px 16px rgba(0, 0, 0, 0.25); }}
+figcaption {{ margin-top: 8px; text-align: center; color: #6c6c70; font-size: 13px; }}
+</style>
+</head>
+<body>
+<figure>{}<figcaption>Front</figcaption></figure>
+<figure>{}<figcaption>Back</figcaption></figure>
+</body>
+</html>
+"#,
+            escape(&self.title),
+            FONT,
+            self.front,
+            self.back
+        )
+    }
+
+    /// Front of pass as PNG image, `scale` is number of pixels per point.
+    ///
+    /// Requires `preview-png` feature. Text is drawn with system fonts.
+    #[cfg(feature = "preview-png")]
+    pub fn front_png(&self, scale: f32) -> Result<Vec<u8>, Error> {
+        rasterize(&self.front, scale)
+    }
+
+    /// Back of pass as PNG image, `scale` is number of pixels per point.
+    ///
+    /// Requires `preview-png` feature. Text is drawn with system fonts.
+    #[cfg(feature = "preview-png")]
+    pub fn back_png(&self, scale: f32) -> Result<Vec<u8>, Error> {
+        rasterize(&self.back, scale)
+    }
+}
+
+/// Colors of pass, from visual appearance or Wallet defaults
+struct Colors {
+    background: String,
+    foreground: String,
+    label: String,
+}
+
+impl Colors {
+    fn new(package: &Package) -> Self {
+        let appearance = package.pass.appearance.as_ref();
+        let color =
+            |color: Option<String>, default: &str| color.unwrap_or_else(|| String::from(default));
+        let foreground = color(
+            appearance.and_then(|a| a.foreground_color.as_ref().map(|c| c.to_string())),
+            "rgb(0, 0, 0)",
+        );
+        Self {
+            background: color(
+                appearance.and_then(|a| a.background_color.as_ref().map(|c| c.to_string())),
+                "rgb(255, 255, 255)",
+            ),
+            label: color(
+                appearance.and_then(|a| a.label_color.as_ref().map(|c| c.to_string())),
+                &foreground,
+            ),
+            foreground,
+        }
+    }
+}
+
+/// SVG drawing of one side
+struct Canvas {
+    body: String,
+}
+
+impl Canvas {
+    fn new() -> Self {
+        Self {
+            body: String::new(),
+        }
+    }
+
+    fn text(&mut self, x: u32, y: u32, size: u32, color: &str, anchor: &str, text: &str) {
+        let _ = write!(
+            self.body,
+            r#"<text x="{}" y="{}" font-size="{}" fill="{}" text-anchor="{}">{}</text>"#,
+            x,
+            y,
+            size,
+            color,
+            anchor,
+            escape(text)
+        );
+    }
+
+    fn image(&mut self, x: u32, y: u32, width: u32, height: u32, data: &[u8], fit: &str) {
+        let _ = write!(
+            self.body,
+            r#"<image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="{}" href="data:image/png;base64,{}"/>"#,
+            x,
+            y,
+            width,
+            height,
+            fit,
+            openssl::base64::encode_block(data)
+        );
+    }
+
+    /// Draw label and value of field, returns height
+    fn field(
+        &mut self,
+        x: u32,
+        y: u32,
+        field: &Content,
+        value_size: u32,
+        anchor: &str,
+        colors: &Colors,
+    ) -> u32 {
+        let label = field.options.label.as_deref().unwrap_or_default();
+        self.text(x, y + 9, 9, &colors.label, anchor, &label.to_uppercase());
+        self.text(
+            x,
+            y + 12 + value_size,
+            value_size,
+            &colors.foreground,
+            anchor,
+            &field.value.to_string(),
+        );
+        value_size + 18
+    }
+
+    /// Draw fields in one row, returns height
+    fn row(&mut self, y: u32, fields: &[&Content], value_size: u32, colors: &Colors) -> u32 {
+        if fields.is_empty() {
+            return 0;
+        }
+        let column = (WIDTH - PADDING * 2) / fields.len() as u32;
+        let mut height = 0;
+        for (i, field) in fields.iter().enumerate() {
+            // Last field of row is aligned to the right edge
+            let (x, anchor) = if i > 0 && i == fields.len() - 1 {
+                (WIDTH - PADDING, "end")
+            } else {
+                (PADDING + column * i as u32, "start")
+            };
+            height = height.max(self.field(x, y, field, value_size, anchor, colors));
+        }
+        height
+    }
+
+    /// Wrap drawing to SVG document
+    fn finish(self, height: u32, background: &str) -> String {
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="{}"><rect width="{w}" height="{h}" fill="{}"/>{}</svg>"#,
+            FONT,
+            background,
+            self.body,
+            w = WIDTH,
+            h = height
+        )
+    }
+}
+
+/// Draw front of pass
+fn front(package: &Package, colors: &Colors) -> String {
+    let pass = &package.pass;
+    let fields = pass.fields.pass_fields();
+    let hidden = pass.hidden_fields(&package.image_set());
+    let visible = |group: FieldGroup| -> Vec<&Content> {
+        fields
+            .group(group)
+            .iter()
+            .filter(|field| {
+                !hidden
+                    .iter()
+                    .any(|(g, hidden)| *g == group && std::ptr::eq(*hidden, *field))
+            })
+            .collect()
+    };
+
+    let (strip_allowed, thumbnail_allowed) = match pass.fields {
+        Type::Coupon { .. } | Type::StoreCard { .. } => (true, false),
+        Type::EventTicket { .. } => (true, true),
+        Type::Generic { .. } => (false, true),
+        Type::BoardingPass { .. } => (false, false),
+    };
+    let strip = image(package, resource::Type::Strip).filter(|_| strip_allowed);
+    let thumbnail =
+        image(package, resource::Type::Thumbnail).filter(|_| thumbnail_allowed && strip.is_none());
+
+    let mut canvas = Canvas::new();
+
+    // Event ticket shows blurred background image
+    if let (Type::EventTicket { .. }, None, Some(background)) = (
+        &pass.fields,
+        strip,
+        image(package, resource::Type::Background),
+    ) {
+        canvas.body.push_str(r#"<filter id="blur"><feGaussianBlur stdDeviation="8"/></filter><g filter="url(#blur)">"#);
+        canvas.image(0, 0, WIDTH, 420, background.as_bytes(), "xMidYMid slice");
+        canvas.body.push_str("</g>");
+    }
+
+    // Header: logo, logo text and header fields
+    let mut logo_end = PADDING;
+    if let Some(logo) = image(package, resource::Type::Logo) {
+        canvas.image(PADDING, 8, 100, 34, logo.as_bytes(), "xMinYMid meet");
+        logo_end += 108;
+    }
+    if let Some(logo_text) = &pass.logo_text {
+        canvas.text(logo_end, 31, 16, &colors.foreground, "start", logo_text);
+    }
+    for (i, field) in visible(FieldGroup::Header).iter().rev().enumerate() {
+        canvas.field(
+            WIDTH - PADDING - i as u32 * 80,
+            10,
+            field,
+            14,
+            "end",
+            colors,
+        );
+    }
+    let mut y = 52;
+
+    // Primary fields
+    let primary = visible(FieldGroup::Primary);
+    if let Type::BoardingPass { transit_type, .. } = &pass.fields {
+        let mut height = 0;
+        if let Some(field) = primary.first() {
+            height = canvas.field(PADDING, y, field, 32, "start", colors);
+        }
+        if let Some(field) = primary.get(1) {
+            height = height.max(canvas.field(WIDTH - PADDING, y, field, 32, "end", colors));
+        }
+        let glyph = match transit_type {
+            TransitType::Air => "✈",
+            _ => "→",
+        };
+        canvas.text(WIDTH / 2, y + 40, 24, &colors.label, "middle", glyph);
+        y += height.max(56) + 8;
+    } else if let Some(strip) = strip {
+        canvas.image(0, y, WIDTH, 123, strip.as_bytes(), "xMidYMid slice");
+        if let Some(field) = primary.first() {
+            canvas.field(PADDING, y + 58, field, 36, "start", colors);
+        }
+        y += 123 + 8;
+    } else {
+        let mut height = 0;
+        if let Some(field) = primary.first() {
+            height = canvas.field(PADDING, y + 8, field, 28, "start", colors) + 8;
+        }
+        if let Some(thumbnail) = thumbnail {
+            canvas.image(
+                WIDTH - PADDING - 90,
+                y,
+                90,
+                90,
+                thumbnail.as_bytes(),
+                "xMaxYMin meet",
+            );
+            height = height.max(98);
+        }
+        y += height + 8;
+    }
+
+    // Secondary and auxiliary fields, combined in one row for coupon and store card
+    let secondary = visible(FieldGroup::Secondary);
+    let auxiliary = visible(FieldGroup::Auxiliary);
+    if matches!(pass.fields, Type::Coupon { .. } | Type::StoreCard { .. }) {
+        let combined: Vec<&Content> = secondary.into_iter().chain(auxiliary).collect();
+        y += canvas.row(y, &combined, 14, colors) + 8;
+    } else {
+        y += canvas.row(y, &secondary, 14, colors) + 8;
+        y += canvas.row(y, &auxiliary, 14, colors) + 8;
+    }
+
+    // Wallet shows the first barcode
+    if let Some(barcode) = pass.barcodes.first() {
+        y += draw_barcode(&mut canvas, y + 8, barcode) + 8;
+    }
+
+    canvas.finish(y + PADDING, &colors.background)
+}
+
+/// Draw barcode in white box at the center, returns height
+fn draw_barcode(canvas: &mut Canvas, y: u32, barcode: &Barcode) -> u32 {
+    let (width, height) = match barcode.format {
+        BarcodeFormat::QR | BarcodeFormat::Aztec => (130, 130),
+        BarcodeFormat::PDF417 => (260, 90),
+        BarcodeFormat::Code128 => (260, 70),
+    };
+    let alt_height = if barcode.alt_text.is_some() { 16 } else { 0 };
+    let x = (WIDTH - width) / 2;
+    let _ = write!(
+        canvas.body,
+        r##"<rect x="{}" y="{}" width="{}" height="{}" rx="6" fill="#fff"/>"##,
+        x - 8,
+        y,
+        width + 16,
+        height + 16 + alt_height
+    );
+
+    #[cfg(feature = "barcode")]
+    let rendered = barcode
+        .render_png(&crate::barcode::RenderOptions {
+            module_size: 4,
+            quiet_zone: 0,
+            bar_height: 20,
+        })
+        .ok();
+    #[cfg(not(feature = "barcode"))]
+    let rendered: Option<Vec<u8>> = None;
+
+    match rendered {
+        Some(png) => {
+            canvas.image(x, y + 8, width, height, &png, "xMidYMid meet");
+        }
+        None => {
+            // Placeholder with message
+            let _ = write!(
+                canvas.body,
+                r##"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#8e8e93" stroke-dasharray="4 3"/>"##,
+                x,
+                y + 8,
+                width,
+                height
+            );
+            canvas.text(
+                WIDTH / 2,
+                y + 8 + height / 2,
+                11,
+                "#8e8e93",
+                "middle",
+                &truncate(&barcode.message, 30),
+            );
+        }
+    }
+    if let Some(alt_text) = &barcode.alt_text {
+        canvas.text(WIDTH / 2, y + height + 20, 10, "#000", "middle", alt_text);
+    }
+    height + 16 + alt_height
+}
+
+/// Draw back of pass
+fn back(package: &Package) -> String {
+    let mut canvas = Canvas::new();
+    let mut y = PADDING;
+    let fields = &package.pass.fields.pass_fields().back_fields;
+
+    if fields.is_empty() {
+        canvas.text(WIDTH / 2, y + 20, 13, "#8e8e93", "middle", "No back fields");
+        y += 32;
+    }
+    for field in fields {
+        if let Some(label) = &field.options.label {
+            canvas.text(PADDING, y + 12, 11, "#6c6c70", "start", label);
+            y += 16;
+        }
+        for line in wrap(&field.value.to_string(), 46) {
+            canvas.text(PADDING, y + 14, 13, "#000", "start", &line);
+            y += 18;
+        }
+        y += 8;
+        let _ = write!(
+            canvas.body,
+            r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#c6c6c8"/>"##,
+            PADDING,
+            y,
+            WIDTH - PADDING,
+            y
+        );
+        y += 8;
+    }
+
+    canvas.finish(y + PADDING, "rgb(242, 242, 247)")
+}
+
+/// Image of package root for display, higher resolution first
+fn image(package: &Package, kind: fn(Version) -> resource::Type) -> Option<&Resource> {
+    [Version::Size2X, Version::Size3X, Version::Standard]
+        .into_iter()
+        .find_map(|version| {
+            package
+                .resources
+                .iter()
+                .find(|resource| resource.get_type() == kind(version.clone()))
+        })
+}
+
+/// Split text to lines, not longer than `width` characters
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + word.chars().count() >= width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Cut text to `width` characters
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return String::from(text);
+    }
+    let mut text: String = text.chars().take(width - 1).collect();
+    text.push('…');
+    text
+}
+
+/// Escape text for XML and HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(feature = "preview-png")]
+fn rasterize(svg: &str, scale: f32) -> Result<Vec<u8>, Error> {
+    use resvg::{tiny_skia, usvg};
+
+    let render_error = |message: String| Error::Parse {
+        file: String::from("preview"),
+        message,
+    };
+
+    let mut options = usvg::Options::default();
+    let fontdb = options.fontdb_mut();
+    fontdb.load_system_fonts();
+    // Generic sans-serif family falls back to installed sans font, if default one is missed
+    let sans_serif = fontdb
+        .family_name(&usvg::fontdb::Family::SansSerif)
+        .to_string();
+    let families: Vec<String> = fontdb
+        .faces()
+        .filter_map(|face| face.families.first().map(|(family, _)| family.clone()))
+        .collect();
+    if !families.contains(&sans_serif) {
+        let family = families
+            .iter()
+            .find(|family| family.contains("Sans") && !family.contains("Mono"))
+            .or(families.first());
+        if let Some(family) = family {
+            fontdb.set_sans_serif_family(family.clone());
+        }
+    }
+
+    let tree = usvg::Tree::from_str(svg, &options).map_err(|err| render_error(err.to_string()))?;
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or_else(|| render_error(format!("invalid scale {}", scale)))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| render_error(format!("invalid scale {}", scale)))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    pixmap
+        .encode_png()
+        .map_err(|err| render_error(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fields::ContentOptions,
+        visual_appearance::{Color, VisualAppearance},
+        PassBuilder, PassConfig,
+    };
+
+    use super::*;
+
+    fn make_package() -> Package {
+        let label = |label: &str| ContentOptions {
+            label: Some(String::from(label)),
+            ..Default::default()
+        };
+        let pass = PassBuilder::new(PassConfig {
+            organization_name: String::from("Apple inc."),
+            description: String::from("Example <pass>"),
+            pass_type_identifier: String::from("com.example.pass"),
+            team_identifier: String::from("AA00AA0A0A"),
+            serial_number: String::from("ABCDEFG1234567890"),
+        })
+        .appearance(VisualAppearance {
+            label_color: Color::new(200, 200, 200),
+            foreground_color: Color::white(),
+            background_color: Color::new(10, 20, 30),
+            footer_background_color: None,
+        })
+        .logo_text(String::from("Concert"))
+        .fields(
+            Type::EventTicket {
+                pass_fields: Default::default(),
+            }
+            .add_header_field(Content::new("date", "Feb 7", label("Date")))
+            .add_primary_field(Content::new("event", "Rock & Roll", label("Event")))
+            .add_primary_field(Content::new("hidden", "Hidden value", label("Hidden")))
+            .add_secondary_field(Content::new("seat", "A12", label("Seat")))
+            .add_back_field(Content::new("terms", "No refunds", label("Terms"))),
+        )
+        .add_barcode(Barcode {
+            message: String::from("TICKET-1"),
+            format: BarcodeFormat::QR,
+            alt_text: Some(String::from("TICKET-1")),
+            ..Default::default()
+        })
+        .build();
+
+        let mut package = Package::new(pass);
+        package
+            .add_resource(
+                resource::Type::Logo(Version::Size2X),
+                &include_bytes!("../examples/pass-generator/template_app_icon.png")[..],
+            )
+            .unwrap();
+        package
+    }
+
+    #[test]
+    fn make_html_preview() {
+        let preview = Preview::new(&make_package());
+
+        // Appearance, header, logo and visible fields
+        assert!(preview.front.contains(r#"fill="rgb(10, 20, 30)""#));
+        assert!(preview.front.contains(">Concert</text>"));
+        assert!(preview.front.contains(">Feb 7</text>"));
+        assert!(preview.front.contains(">Rock &amp; Roll</text>"));
+        assert!(preview.front.contains(">SEAT</text>"));
+        assert!(preview.front.contains("data:image/png;base64,"));
+        assert!(preview.front.contains(">TICKET-1</text>"));
+
+        // Second primary field is hidden by Wallet
+        assert!(!preview.front.contains("Hidden value"));
+
+        // Back fields
+        assert!(preview.back.contains(">Terms</text>"));
+        assert!(preview.back.contains(">No refunds</text>"));
+
+        let html = preview.html();
+        assert!(html.contains("<title>Example &lt;pass&gt;</title>"));
+        assert!(html.contains(&preview.front));
+        assert!(html.contains(&preview.back));
+    }
+
+    #[test]
+    fn wrap_text() {
+        assert_eq!(
+            vec!["first line", "second", "third line"],
+            wrap("first line second\nthird line", 12)
+        );
+        assert_eq!("abc…", truncate("abcdef", 4));
+    }
+
+    #[cfg(feature = "preview-png")]
+    #[test]
+    fn make_png_preview() {
+        let preview = Preview::new(&make_package());
+        let png = preview.front_png(2.0).unwrap();
+
+        // Width from PNG header (IHDR)
+        assert_eq!(b"\x89PNG", &png[..4]);
+        let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        assert_eq!(WIDTH * 2, width);
+    }
+}
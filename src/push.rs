@@ -0,0 +1,525 @@
+//! Push notifications about pass updates through Apple Push Notification service (APNs).
+//!
+//! When a pass changes, every device registered for the pass receives push notification with empty payload,
+//! then device asks [web service](crate::web_service::WebService) for updated serial numbers and fetches new pass.
+//!
+//! [PushRequest] builds requests, [PushTransport] sends them. [MockTransport] records requests for tests,
+//! HTTP/2 transport for APNs and local APNs stand-in are in `apns` module (`push` feature).
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use passes::{
+//!     push::{MockTransport, PassUpdateNotifier},
+//!     store::{MemoryStore, RegistrationStore},
+//!     PassConfig,
+//! };
+//!
+//! let store = MemoryStore::new();
+//! store
+//!     .register("device1", "push-token", "com.example.pass", "ABC123")
+//!     .unwrap();
+//!
+//! let transport = Arc::new(MockTransport::new());
+//! let notifier = PassUpdateNotifier::new(transport.clone());
+//!
+//! let config = PassConfig {
+//!     organization_name: String::from("Apple inc."),
+//!     description: String::from("Example pass"),
+//!     pass_type_identifier: String::from("com.example.pass"),
+//!     team_identifier: String::from("AA00AA0A0A"),
+//!     serial_number: String::from("ABC123"),
+//! };
+//! notifier.notify_updated(&store, &config).unwrap();
+//!
+//! let requests = transport.requests();
+//! assert_eq!("/3/device/push-token", requests[0].path());
+//! assert_eq!(Some("com.example.pass"), requests[0].header("apns-topic"));
+//! ```
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use openssl::{
+    ecdsa::EcdsaSig,
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{PKey, Private},
+};
+
+use crate::{store::RegistrationStore, Error, PassConfig};
+
+#[cfg(feature = "push")]
+pub mod apns;
+
+/// APNs production server
+pub const PRODUCTION_URL: &str = "https://api.push.apple.com";
+
+/// APNs development server
+pub const DEVELOPMENT_URL: &str = "https://api.sandbox.push.apple.com";
+
+/// Provider tokens must be refreshed at least once an hour
+const TOKEN_LIFETIME: i64 = 50 * 60;
+
+/// Push notification request to APNs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushRequest {
+    /// Push token of device
+    pub device_token: String,
+
+    /// Request headers (names in lower case)
+    pub headers: Vec<(String, String)>,
+
+    /// JSON payload
+    pub body: Vec<u8>,
+}
+
+impl PushRequest {
+    /// Request for pass update notification.
+    ///
+    /// Topic is [pass_type_identifier](PassConfig::pass_type_identifier), payload is empty JSON dictionary.
+    pub fn pass_update(config: &PassConfig, device_token: &str) -> Self {
+        Self {
+            device_token: String::from(device_token),
+            headers: vec![
+                (
+                    String::from("apns-topic"),
+                    config.pass_type_identifier.clone(),
+                ),
+                (String::from("apns-push-type"), String::from("background")),
+                (String::from("apns-priority"), String::from("5")),
+            ],
+            body: b"{}".to_vec(),
+        }
+    }
+
+    /// Add `authorization` header with provider token.
+    ///
+    /// Not required if transport uses pass type certificate.
+    pub fn with_token(mut self, auth: &TokenAuth) -> Result<Self, Error> {
+        let token = auth.token()?;
+        self.headers
+            .push((String::from("authorization"), format!("bearer {}", token)));
+        Ok(self)
+    }
+
+    /// Request path, e.g. `/3/device/<token>`
+    pub fn path(&self) -> String {
+        format!("/3/device/{}", self.device_token)
+    }
+
+    /// Value of header by name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Response from APNs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushResponse {
+    /// HTTP status code
+    pub status: u16,
+
+    /// Value of `apns-id` header
+    pub apns_id: Option<String>,
+
+    /// Reason of error, e.g. `BadDeviceToken`
+    pub reason: Option<String>,
+}
+
+impl PushResponse {
+    /// Successful response
+    pub fn ok() -> Self {
+        Self {
+            status: 200,
+            apns_id: None,
+            reason: None,
+        }
+    }
+
+    /// Notification is accepted by APNs
+    pub fn is_success(&self) -> bool {
+        self.status == 200
+    }
+
+    /// Device token is no longer active, device should be unregistered
+    pub fn is_unregistered(&self) -> bool {
+        self.status == 410
+    }
+}
+
+/// Transport, that delivers push requests to APNs
+pub trait PushTransport: Send + Sync {
+    /// Send single request
+    fn send(&self, request: &PushRequest) -> Result<PushResponse, Error>;
+}
+
+/// Token-based authentication with APNs signing key (.p8 file).
+///
+/// Provider token is JWT, signed with ES256. Token is cached and refreshed every 50 minutes.
+pub struct TokenAuth {
+    key_id: String,
+    team_id: String,
+    key: PKey<Private>,
+    cached: Mutex<Option<(i64, String)>>,
+}
+
+impl TokenAuth {
+    /// Create authentication from key identifier, team identifier and PEM key (.p8 file from Apple Developer portal)
+    pub fn new(key_id: &str, team_id: &str, key: &[u8]) -> Result<Self, Error> {
+        let key = PKey::private_key_from_pem(key).map_err(|source| Error::Sign {
+            file: Some(String::from("APNs key")),
+            source,
+        })?;
+        let is_p256 = key
+            .ec_key()
+            .ok()
+            .and_then(|ec_key| ec_key.group().curve_name())
+            == Some(Nid::X9_62_PRIME256V1);
+        if !is_p256 {
+            return Err(Error::Validation(String::from(
+                "APNs key must be P-256 elliptic curve key",
+            )));
+        }
+
+        Ok(Self {
+            key_id: String::from(key_id),
+            team_id: String::from(team_id),
+            key,
+            cached: Mutex::new(None),
+        })
+    }
+
+    /// Provider token for current time
+    pub fn token(&self) -> Result<String, Error> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or(0);
+
+        let mut cached = self.cached.lock().unwrap_or_else(PoisonError::into_inner);
+        match &*cached {
+            Some((issued_at, token)) if now - issued_at < TOKEN_LIFETIME => Ok(token.clone()),
+            _ => {
+                let token = self.make_token(now)?;
+                *cached = Some((now, token.clone()));
+                Ok(token)
+            }
+        }
+    }
+
+    /// Make provider token, issued at unix time
+    pub fn make_token(&self, issued_at: i64) -> Result<String, Error> {
+        let header = serde_json::json!({ "alg": "ES256", "kid": self.key_id });
+        let claims = serde_json::json!({ "iss": self.team_id, "iat": issued_at });
+        let input = format!(
+            "{}.{}",
+            base64_url(header.to_string().as_bytes()),
+            base64_url(claims.to_string().as_bytes())
+        );
+
+        let signature = self.sign(input.as_bytes()).map_err(|source| Error::Sign {
+            file: Some(String::from("APNs key")),
+            source,
+        })?;
+        Ok(format!("{}.{}", input, base64_url(&signature)))
+    }
+
+    /// ES256 signature in JWS format (r and s, 32 bytes each)
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, openssl::error::ErrorStack> {
+        let mut signer = openssl::sign::Signer::new(MessageDigest::sha256(), &self.key)?;
+        signer.update(data)?;
+        let signature = EcdsaSig::from_der(&signer.sign_to_vec()?)?;
+
+        let mut raw = signature.r().to_vec_padded(32)?;
+        raw.extend(signature.s().to_vec_padded(32)?);
+        Ok(raw)
+    }
+}
+
+/// Base64 URL encoding without padding, as used in JWT
+fn base64_url(data: &[u8]) -> String {
+    openssl::base64::encode_block(data)
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_")
+}
+
+/// Push token of device and result of sending notification to it
+pub type NotifyResult = (String, Result<PushResponse, Error>);
+
+/// Sends pass update notifications to registered devices
+pub struct PassUpdateNotifier {
+    transport: Arc<dyn PushTransport>,
+    auth: Option<TokenAuth>,
+}
+
+impl PassUpdateNotifier {
+    /// Create notifier with transport.
+    ///
+    /// Without [token authentication](PassUpdateNotifier::token_auth) transport must use pass type certificate.
+    pub fn new(transport: Arc<dyn PushTransport>) -> Self {
+        Self {
+            transport,
+            auth: None,
+        }
+    }
+
+    /// Use token-based authentication
+    pub fn token_auth(mut self, auth: TokenAuth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Send update notification to devices with push tokens.
+    ///
+    /// Returns push token and result for every device, failed request doesn't stop sending to others.
+    pub fn notify(&self, config: &PassConfig, push_tokens: &[String]) -> Vec<NotifyResult> {
+        push_tokens
+            .iter()
+            .map(|push_token| {
+                let mut request = PushRequest::pass_update(config, push_token);
+                if let Some(auth) = &self.auth {
+                    request = match request.with_token(auth) {
+                        Ok(request) => request,
+                        Err(err) => return (push_token.clone(), Err(err)),
+                    };
+                }
+                (push_token.clone(), self.transport.send(&request))
+            })
+            .collect()
+    }
+
+    /// Mark pass as updated in store and notify all registered devices.
+    ///
+    /// Returns push token and result for every device, see [notify](PassUpdateNotifier::notify).
+    pub fn notify_updated(
+        &self,
+        store: &dyn RegistrationStore,
+        config: &PassConfig,
+    ) -> Result<Vec<NotifyResult>, Error> {
+        store.mark_updated(&config.pass_type_identifier, &config.serial_number)?;
+        let push_tokens = store.push_tokens(&config.pass_type_identifier, &config.serial_number)?;
+        Ok(self.notify(config, &push_tokens))
+    }
+}
+
+/// Transport for tests, records requests instead of sending
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    requests: Mutex<Vec<PushRequest>>,
+    responses: Mutex<BTreeMap<String, PushResponse>>,
+}
+
+impl MockTransport {
+    /// Create transport, that accepts all requests
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set response for device token (by default response is [ok](PushResponse::ok))
+    pub fn respond_with(&self, device_token: &str, response: PushResponse) {
+        lock(&self.responses).insert(String::from(device_token), response);
+    }
+
+    /// Sent requests
+    pub fn requests(&self) -> Vec<PushRequest> {
+        lock(&self.requests).clone()
+    }
+}
+
+impl PushTransport for MockTransport {
+    fn send(&self, request: &PushRequest) -> Result<PushResponse, Error> {
+        lock(&self.requests).push(request.clone());
+        let response = lock(&self.responses)
+            .get(&request.device_token)
+            .cloned()
+            .unwrap_or_else(PushResponse::ok);
+        Ok(response)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use openssl::{
+        bn::BigNum,
+        ec::{EcGroup, EcKey},
+    };
+
+    use crate::store::MemoryStore;
+
+    use super::*;
+
+    fn make_config() -> PassConfig {
+        PassConfig {
+            organization_name: String::from("Apple inc."),
+            description: String::from("Example pass"),
+            pass_type_identifier: String::from("com.example.pass"),
+            team_identifier: String::from("AA00AA0A0A"),
+            serial_number: String::from("ABC123"),
+        }
+    }
+
+    fn base64_url_decode(data: &str) -> Vec<u8> {
+        let mut data = data.replace('-', "+").replace('_', "/");
+        while !data.len().is_multiple_of(4) {
+            data.push('=');
+        }
+        openssl::base64::decode_block(&data).unwrap()
+    }
+
+    /// Check ES256 signature in JWS format
+    fn verify_signature(key: &PKey<Private>, data: &[u8], signature: &[u8]) -> bool {
+        let r = BigNum::from_slice(&signature[..32]).unwrap();
+        let s = BigNum::from_slice(&signature[32..]).unwrap();
+        let der = EcdsaSig::from_private_components(r, s)
+            .unwrap()
+            .to_der()
+            .unwrap();
+        let mut verifier = openssl::sign::Verifier::new(MessageDigest::sha256(), key).unwrap();
+        verifier.update(data).unwrap();
+        verifier.verify(&der).unwrap()
+    }
+
+    fn make_key() -> Vec<u8> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        key.private_key_to_pem_pkcs8().unwrap()
+    }
+
+    #[test]
+    fn make_pass_update_request() {
+        let request = PushRequest::pass_update(&make_config(), "0123abcd");
+
+        assert_eq!("/3/device/0123abcd", request.path());
+        assert_eq!(Some("com.example.pass"), request.header("apns-topic"));
+        assert_eq!(Some("background"), request.header("APNs-Push-Type"));
+        assert_eq!(None, request.header("authorization"));
+        assert_eq!(b"{}".to_vec(), request.body);
+    }
+
+    #[test]
+    fn make_provider_token() {
+        let key = make_key();
+        let auth = TokenAuth::new("KEY1234567", "AA00AA0A0A", &key).unwrap();
+
+        let token = auth.make_token(1700000000).unwrap();
+        let parts: Vec<&str> = token.split('.').collect();
+        assert_eq!(3, parts.len());
+        assert_eq!(
+            base64_url(br#"{"alg":"ES256","kid":"KEY1234567"}"#),
+            parts[0]
+        );
+        assert_eq!(
+            base64_url(br#"{"iat":1700000000,"iss":"AA00AA0A0A"}"#),
+            parts[1]
+        );
+
+        // Signature is valid ES256 signature of header and claims
+        let signature = base64_url_decode(parts[2]);
+        assert_eq!(64, signature.len());
+        let key = PKey::private_key_from_pem(&key).unwrap();
+        let input = format!("{}.{}", parts[0], parts[1]);
+        assert!(verify_signature(&key, input.as_bytes(), &signature));
+
+        // Token is cached
+        assert_eq!(auth.token().unwrap(), auth.token().unwrap());
+
+        let request = PushRequest::pass_update(&make_config(), "0123abcd")
+            .with_token(&auth)
+            .unwrap();
+        assert!(request
+            .header("authorization")
+            .unwrap()
+            .starts_with("bearer "));
+    }
+
+    #[test]
+    fn reject_non_p256_key() {
+        let key = PKey::from_rsa(openssl::rsa::Rsa::generate(2048).unwrap())
+            .unwrap()
+            .private_key_to_pem_pkcs8()
+            .unwrap();
+        assert!(TokenAuth::new("KEY1234567", "AA00AA0A0A", &key).is_err());
+    }
+
+    #[test]
+    fn notify_registered_devices() {
+        let store = MemoryStore::new();
+        store
+            .register("device1", "token1", "com.example.pass", "ABC123")
+            .unwrap();
+        store
+            .register("device2", "token2", "com.example.pass", "ABC123")
+            .unwrap();
+        store
+            .register("device3", "token3", "com.example.pass", "OTHER")
+            .unwrap();
+
+        let transport = Arc::new(MockTransport::new());
+        transport.respond_with(
+            "token2",
+            PushResponse {
+                status: 410,
+                apns_id: None,
+                reason: Some(String::from("Unregistered")),
+            },
+        );
+        let notifier = PassUpdateNotifier::new(transport.clone());
+
+        let responses = notifier.notify_updated(&store, &make_config()).unwrap();
+
+        assert_eq!(2, responses.len());
+        assert_eq!("token1", responses[0].0);
+        assert!(responses[0].1.as_ref().unwrap().is_success());
+        assert!(responses[1].1.as_ref().unwrap().is_unregistered());
+        assert_eq!(2, transport.requests().len());
+
+        // Pass is marked as updated
+        let updated = store
+            .updated_serials("device1", "com.example.pass", Some(0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec!["ABC123"], updated.serial_numbers);
+    }
+
+    #[test]
+    fn notify_after_failed_request() {
+        /// Transport, that fails for the first device
+        struct FailingTransport(MockTransport);
+
+        impl PushTransport for FailingTransport {
+            fn send(&self, request: &PushRequest) -> Result<PushResponse, Error> {
+                if request.device_token == "token1" {
+                    return Err(Error::Io {
+                        file: None,
+                        source: std::io::Error::other("connection reset"),
+                    });
+                }
+                self.0.send(request)
+            }
+        }
+
+        let transport = Arc::new(FailingTransport(MockTransport::new()));
+        let notifier = PassUpdateNotifier::new(transport.clone());
+        let push_tokens = vec![String::from("token1"), String::from("token2")];
+
+        let responses = notifier.notify(&make_config(), &push_tokens);
+
+        assert_eq!(2, responses.len());
+        assert_eq!("token1", responses[0].0);
+        assert!(matches!(responses[0].1, Err(Error::Io { .. })));
+        assert_eq!("token2", responses[1].0);
+        assert!(responses[1].1.as_ref().unwrap().is_success());
+        assert_eq!(1, transport.0.requests().len());
+    }
+}
@@ -0,0 +1,512 @@
+use std::{
+    collections::BTreeSet,
+    net::{SocketAddr, TcpListener as StdTcpListener, TcpStream as StdTcpStream},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+};
+
+use bytes::Bytes;
+use h2::client::SendRequest;
+use openssl::ssl::{SslConnector, SslMethod};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    runtime::Runtime,
+};
+use tokio_openssl::SslStream;
+
+use crate::{sign::SignConfig, Error};
+
+use super::{lock, PushRequest, PushResponse, PushTransport};
+
+/// HTTP/2 transport to APNs.
+///
+/// Requires `push` feature. Connection is opened on first request and reused for next requests.
+///
+/// Transport has its own runtime, [send](PushTransport::send) blocks current thread. It can be called
+/// from async code too (request is sent from separate thread then), but prefer
+/// [spawn_blocking](https://docs.rs/tokio/latest/tokio/task/fn.spawn_blocking.html) there.
+///
+/// ```no_run
+/// use passes::{
+///     push::{apns::ApnsTransport, PushRequest, PushTransport, PRODUCTION_URL},
+///     sign::{SignConfig, WWDR},
+/// };
+/// # use passes::PassConfig;
+/// # let config = PassConfig {
+/// #     organization_name: String::from("Apple inc."),
+/// #     description: String::from("Example pass"),
+/// #     pass_type_identifier: String::from("com.example.pass"),
+/// #     team_identifier: String::from("AA00AA0A0A"),
+/// #     serial_number: String::from("ABC123"),
+/// # };
+///
+/// let p12 = std::fs::read("certs/pass.p12").unwrap();
+/// let sign_config = SignConfig::from_pkcs12(&p12, "password", WWDR::G4).unwrap();
+///
+/// // Authentication with pass type certificate
+/// let transport = ApnsTransport::with_certificate(PRODUCTION_URL, &sign_config).unwrap();
+/// let response = transport
+///     .send(&PushRequest::pass_update(&config, "device-push-token"))
+///     .unwrap();
+/// ```
+pub struct ApnsTransport {
+    scheme: String,
+    host: String,
+    port: u16,
+    tls: Option<SslConnector>,
+    runtime: Option<Runtime>,
+    connection: Mutex<Option<SendRequest<Bytes>>>,
+}
+
+impl ApnsTransport {
+    /// Create transport for server URL (e.g. [PRODUCTION_URL](super::PRODUCTION_URL)), for token-based authentication.
+    ///
+    /// `http://` URL uses HTTP/2 without TLS, for local servers.
+    pub fn new(url: &str) -> Result<Self, Error> {
+        Self::build(url, None)
+    }
+
+    /// Create transport, authenticated with pass type certificate from sign config
+    pub fn with_certificate(url: &str, config: &SignConfig) -> Result<Self, Error> {
+        Self::build(url, Some(config))
+    }
+
+    fn build(url: &str, config: Option<&SignConfig>) -> Result<Self, Error> {
+        let uri: http::Uri = url.parse().map_err(push_error)?;
+        let scheme = uri.scheme_str().unwrap_or("https").to_lowercase();
+        let host = uri
+            .host()
+            .ok_or_else(|| Error::Validation(format!("APNs URL has no host: {}", url)))?;
+        let port = uri
+            .port_u16()
+            .unwrap_or(if scheme == "http" { 80 } else { 443 });
+
+        let tls = if scheme == "https" {
+            let sign_error = |source| Error::Sign {
+                file: Some(String::from("APNs certificate")),
+                source,
+            };
+            let mut builder = SslConnector::builder(SslMethod::tls_client()).map_err(sign_error)?;
+            builder.set_alpn_protos(b"\x02h2").map_err(sign_error)?;
+            if let Some(config) = config {
+                builder
+                    .set_certificate(&config.sign_cert)
+                    .map_err(sign_error)?;
+                builder
+                    .set_private_key(&config.sign_key)
+                    .map_err(sign_error)?;
+            }
+            Some(builder.build())
+        } else {
+            None
+        };
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .map_err(|source| Error::Io { file: None, source })?;
+
+        Ok(Self {
+            scheme,
+            host: String::from(host),
+            port,
+            tls,
+            runtime: Some(runtime),
+            connection: Mutex::new(None),
+        })
+    }
+
+    async fn connect(&self) -> Result<SendRequest<Bytes>, Error> {
+        let tcp = TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .map_err(push_error)?;
+
+        let send_request = match &self.tls {
+            Some(tls) => {
+                let ssl = tls
+                    .configure()
+                    .and_then(|config| config.into_ssl(&self.host))
+                    .map_err(push_error)?;
+                let mut stream = SslStream::new(ssl, tcp).map_err(push_error)?;
+                Pin::new(&mut stream).connect().await.map_err(push_error)?;
+                let (send_request, connection) =
+                    h2::client::handshake(stream).await.map_err(push_error)?;
+                self.runtime().spawn(connection);
+                send_request
+            }
+            None => {
+                let (send_request, connection) =
+                    h2::client::handshake(tcp).await.map_err(push_error)?;
+                self.runtime().spawn(connection);
+                send_request
+            }
+        };
+        Ok(send_request)
+    }
+
+    async fn send_async(
+        &self,
+        send_request: SendRequest<Bytes>,
+        request: &PushRequest,
+    ) -> Result<PushResponse, Error> {
+        let mut send_request = send_request.ready().await.map_err(push_error)?;
+
+        let mut builder = http::Request::builder().method("POST").uri(format!(
+            "{}://{}:{}{}",
+            self.scheme,
+            self.host,
+            self.port,
+            request.path()
+        ));
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        let http_request = builder.body(()).map_err(push_error)?;
+
+        let (response, mut stream) = send_request
+            .send_request(http_request, false)
+            .map_err(push_error)?;
+        stream
+            .send_data(Bytes::from(request.body.clone()), true)
+            .map_err(push_error)?;
+
+        let response = response.await.map_err(push_error)?;
+        let status = response.status().as_u16();
+        let apns_id = response
+            .headers()
+            .get("apns-id")
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+
+        let mut body = response.into_body();
+        let mut data = Vec::new();
+        while let Some(chunk) = body.data().await {
+            let chunk = chunk.map_err(push_error)?;
+            let _ = body.flow_control().release_capacity(chunk.len());
+            data.extend_from_slice(&chunk);
+        }
+        let reason = serde_json::from_slice::<serde_json::Value>(&data)
+            .ok()
+            .and_then(|json| json["reason"].as_str().map(String::from));
+
+        Ok(PushResponse {
+            status,
+            apns_id,
+            reason,
+        })
+    }
+
+    /// Send request on own runtime, blocks current thread
+    fn send_blocking(&self, request: &PushRequest) -> Result<PushResponse, Error> {
+        let runtime = self.runtime();
+        let mut connection = lock(&self.connection);
+        runtime.block_on(async {
+            let send_request = match connection.take() {
+                Some(send_request) => send_request,
+                None => self.connect().await?,
+            };
+            let response = self.send_async(send_request.clone(), request).await?;
+            // Connection is kept only after successful request
+            *connection = Some(send_request);
+            Ok(response)
+        })
+    }
+
+    fn runtime(&self) -> &Runtime {
+        self.runtime
+            .as_ref()
+            .expect("runtime is taken only on drop")
+    }
+}
+
+impl PushTransport for ApnsTransport {
+    fn send(&self, request: &PushRequest) -> Result<PushResponse, Error> {
+        // Runtime can't be started from task of other runtime, so request is sent from separate thread
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return std::thread::scope(|scope| {
+                scope
+                    .spawn(|| self.send_blocking(request))
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            });
+        }
+        self.send_blocking(request)
+    }
+}
+
+impl Drop for ApnsTransport {
+    fn drop(&mut self) {
+        // Unlike drop of runtime, doesn't panic if transport is dropped in async code
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Local HTTP/2 server, that acts as APNs for tests.
+///
+/// Requires `push` feature. Server accepts HTTP/2 without TLS, records requests and answers
+/// `200` or `410 Unregistered` for [unregistered](LocalApns::unregister) tokens.
+///
+/// ```
+/// use passes::push::{apns::{ApnsTransport, LocalApns}, PushRequest, PushTransport};
+/// # use passes::PassConfig;
+/// # let config = PassConfig {
+/// #     organization_name: String::from("Apple inc."),
+/// #     description: String::from("Example pass"),
+/// #     pass_type_identifier: String::from("com.example.pass"),
+/// #     team_identifier: String::from("AA00AA0A0A"),
+/// #     serial_number: String::from("ABC123"),
+/// # };
+///
+/// let apns = LocalApns::start().unwrap();
+/// let transport = ApnsTransport::new(&apns.url()).unwrap();
+///
+/// let response = transport
+///     .send(&PushRequest::pass_update(&config, "token1"))
+///     .unwrap();
+/// assert!(response.is_success());
+/// assert_eq!("token1", apns.requests()[0].device_token);
+/// ```
+pub struct LocalApns {
+    addr: SocketAddr,
+    state: Arc<LocalState>,
+    thread: Option<JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct LocalState {
+    requests: Mutex<Vec<PushRequest>>,
+    unregistered: Mutex<BTreeSet<String>>,
+    stopped: AtomicBool,
+}
+
+impl LocalApns {
+    /// Start server on random local port
+    pub fn start() -> Result<Self, Error> {
+        let io_error = |source| Error::Io { file: None, source };
+        let listener = StdTcpListener::bind("127.0.0.1:0").map_err(io_error)?;
+        listener.set_nonblocking(true).map_err(io_error)?;
+        let addr = listener.local_addr().map_err(io_error)?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .map_err(io_error)?;
+
+        let state = Arc::new(LocalState::default());
+        let thread_state = state.clone();
+        let thread = std::thread::spawn(move || {
+            runtime.block_on(async move {
+                let Ok(listener) = TcpListener::from_std(listener) else {
+                    return;
+                };
+                while let Ok((socket, _)) = listener.accept().await {
+                    if thread_state.stopped.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    tokio::spawn(serve_connection(socket, thread_state.clone()));
+                }
+            })
+        });
+
+        Ok(Self {
+            addr,
+            state,
+            thread: Some(thread),
+        })
+    }
+
+    /// Server URL for [ApnsTransport]
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Answer `410 Unregistered` for device token
+    pub fn unregister(&self, device_token: &str) {
+        lock(&self.state.unregistered).insert(String::from(device_token));
+    }
+
+    /// Received requests
+    pub fn requests(&self) -> Vec<PushRequest> {
+        lock(&self.state.requests).clone()
+    }
+}
+
+impl Drop for LocalApns {
+    fn drop(&mut self) {
+        self.state.stopped.store(true, Ordering::SeqCst);
+        // Wake up accept loop
+        let _ = StdTcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+async fn serve_connection(socket: TcpStream, state: Arc<LocalState>) {
+    let Ok(mut connection) = h2::server::handshake(socket).await else {
+        return;
+    };
+    while let Some(Ok((request, respond))) = connection.accept().await {
+        tokio::spawn(serve_request(request, respond, state.clone()));
+    }
+}
+
+async fn serve_request(
+    request: http::Request<h2::RecvStream>,
+    mut respond: h2::server::SendResponse<Bytes>,
+    state: Arc<LocalState>,
+) {
+    let (parts, mut body) = request.into_parts();
+    let mut data = Vec::new();
+    while let Some(Ok(chunk)) = body.data().await {
+        let _ = body.flow_control().release_capacity(chunk.len());
+        data.extend_from_slice(&chunk);
+    }
+
+    let device_token = parts
+        .uri
+        .path()
+        .strip_prefix("/3/device/")
+        .map(String::from);
+    let Some(device_token) = device_token else {
+        let response = http::Response::builder().status(404).body(());
+        if let Ok(response) = response {
+            let _ = respond.send_response(response, true);
+        }
+        return;
+    };
+
+    let headers = parts
+        .headers
+        .iter()
+        .filter_map(|(name, value)| {
+            Some((
+                String::from(name.as_str()),
+                String::from(value.to_str().ok()?),
+            ))
+        })
+        .collect();
+    let unregistered = lock(&state.unregistered).contains(&device_token);
+    lock(&state.requests).push(PushRequest {
+        device_token,
+        headers,
+        body: data,
+    });
+
+    let mut apns_id = [0u8; 16];
+    let _ = openssl::rand::rand_bytes(&mut apns_id);
+    let response = http::Response::builder()
+        .status(if unregistered { 410 } else { 200 })
+        .header("apns-id", hex::encode_upper(apns_id))
+        .body(());
+    let Ok(response) = response else {
+        return;
+    };
+    if unregistered {
+        if let Ok(mut stream) = respond.send_response(response, false) {
+            let _ = stream.send_data(Bytes::from_static(br#"{"reason":"Unregistered"}"#), true);
+        }
+    } else {
+        let _ = respond.send_response(response, true);
+    }
+}
+
+fn push_error<E: std::error::Error + Send + Sync + 'static>(source: E) -> Error {
+    Error::Push {
+        source: Box::new(source),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        push::{PassUpdateNotifier, TokenAuth},
+        store::{MemoryStore, RegistrationStore},
+        PassConfig,
+    };
+
+    use super::*;
+
+    #[test]
+    fn send_to_local_apns() {
+        let apns = LocalApns::start().unwrap();
+        apns.unregister("token2");
+
+        let store = MemoryStore::new();
+        store
+            .register("device1", "token1", "com.example.pass", "ABC123")
+            .unwrap();
+        store
+            .register("device2", "token2", "com.example.pass", "ABC123")
+            .unwrap();
+
+        let group =
+            openssl::ec::EcGroup::from_curve_name(openssl::nid::Nid::X9_62_PRIME256V1).unwrap();
+        let key = openssl::pkey::PKey::from_ec_key(openssl::ec::EcKey::generate(&group).unwrap())
+            .unwrap()
+            .private_key_to_pem_pkcs8()
+            .unwrap();
+
+        let transport = Arc::new(ApnsTransport::new(&apns.url()).unwrap());
+        let notifier = PassUpdateNotifier::new(transport)
+            .token_auth(TokenAuth::new("KEY1234567", "AA00AA0A0A", &key).unwrap());
+
+        let config = PassConfig {
+            organization_name: String::from("Apple inc."),
+            description: String::from("Example pass"),
+            pass_type_identifier: String::from("com.example.pass"),
+            team_identifier: String::from("AA00AA0A0A"),
+            serial_number: String::from("ABC123"),
+        };
+        let responses = notifier.notify_updated(&store, &config).unwrap();
+
+        let response = responses[0].1.as_ref().unwrap();
+        assert!(response.is_success());
+        assert_eq!(32, response.apns_id.as_ref().unwrap().len());
+        let response = responses[1].1.as_ref().unwrap();
+        assert!(response.is_unregistered());
+        assert_eq!(Some("Unregistered"), response.reason.as_deref());
+
+        let requests = apns.requests();
+        assert_eq!(2, requests.len());
+        assert_eq!(Some("com.example.pass"), requests[0].header("apns-topic"));
+        assert!(requests[0]
+            .header("authorization")
+            .unwrap()
+            .starts_with("bearer "));
+        assert_eq!(b"{}".to_vec(), requests[0].body);
+    }
+
+    #[test]
+    fn send_from_async_code() {
+        let apns = LocalApns::start().unwrap();
+        let transport = ApnsTransport::new(&apns.url()).unwrap();
+        let config = PassConfig {
+            organization_name: String::from("Apple inc."),
+            description: String::from("Example pass"),
+            pass_type_identifier: String::from("com.example.pass"),
+            team_identifier: String::from("AA00AA0A0A"),
+            serial_number: String::from("ABC123"),
+        };
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .unwrap();
+        let response = runtime
+            .block_on(async {
+                let response = transport.send(&PushRequest::pass_update(&config, "token1"));
+                // Transport can be dropped in async code
+                drop(transport);
+                response
+            })
+            .unwrap();
+        assert!(response.is_success());
+        assert_eq!(1, apns.requests().len());
+    }
+}
@@ -0,0 +1,754 @@
+//! Web service for pass updates, server side of [PassKit web service protocol](https://developer.apple.com/documentation/walletpasses/adding_a_web_service_to_update_passes).
+//!
+//! Requires `server` feature.
+//!
+//! [WebServiceHandler] implements endpoints for registering devices, getting updated serial numbers,
+//! fetching latest pass, [personalization](WebServiceHandler::on_personalize) and logging. Registrations are kept in [RegistrationStore], passes are made by
+//! user callback, that returns [Package] for pass type identifier and serial number. Set [token callback](WebServiceHandler::on_token)
+//! to check authentication tokens without making packages.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use passes::{server::WebServiceHandler, store::MemoryStore, Package};
+//!
+//! fn load_package(pass_type_identifier: &str, serial_number: &str) -> Option<Package> {
+//!     // Build and sign package from your database
+//!     None
+//! }
+//!
+//! let handler = WebServiceHandler::new(Arc::new(MemoryStore::new()), |pass_type, serial| {
+//!     Ok(load_package(pass_type, serial))
+//! })
+//! .base_path("/passes");
+//!
+//! // webServiceURL in pass.json is https://example.com/passes
+//! let server = tiny_http::Server::http("0.0.0.0:8080").unwrap();
+//! handler.serve(&server);
+//! ```
+use std::{io::Cursor, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{personalization::PersonalizationRequest, store::RegistrationStore, Error, Package};
+
+/// Callback, that returns latest package for pass type identifier and serial number (`None` if pass doesn't exist)
+///
+/// Without [TokenProvider] it's also called for checking authentication token of every register
+/// and unregister request, so package is built (and signer is loaded) for any request with known serial number.
+pub type PassProvider = dyn Fn(&str, &str) -> Result<Option<Package>, Error> + Send + Sync;
+
+/// Callback, that returns authentication token for pass type identifier and serial number (`None` if pass doesn't exist)
+pub type TokenProvider = dyn Fn(&str, &str) -> Result<Option<String>, Error> + Send + Sync;
+
+/// Callback for log messages from devices
+pub type LogHandler = dyn Fn(&str) + Send + Sync;
+
+/// Callback for personalize requests with pass type identifier, serial number and user info
+pub type PersonalizeHandler =
+    dyn Fn(&str, &str, &PersonalizationRequest) -> Result<(), Error> + Send + Sync;
+
+/// Body of register device request
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationRequest {
+    /// Push token, that server uses to send update notifications
+    pub push_token: String,
+}
+
+/// Response for serial numbers request
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialNumbersResponse {
+    /// Serial numbers of updated passes
+    pub serial_numbers: Vec<String>,
+
+    /// Tag of the latest update, device sends it as `passesUpdatedSince` in next request
+    pub last_updated: String,
+}
+
+/// Body of log request
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRequest {
+    /// Log messages from device
+    pub logs: Vec<String>,
+}
+
+/// HTTP request to web service
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// HTTP method (`GET`, `POST`, `DELETE`)
+    pub method: String,
+
+    /// Request URL (path with query)
+    pub url: String,
+
+    /// Value of `Authorization` header
+    pub authorization: Option<String>,
+
+    /// Request body
+    pub body: Vec<u8>,
+}
+
+/// HTTP response of web service
+#[derive(Debug, Clone)]
+pub struct Response {
+    /// HTTP status code
+    pub status: u16,
+
+    /// Value of `Content-Type` header
+    pub content_type: Option<&'static str>,
+
+    /// Response body
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    fn status(status: u16) -> Self {
+        Self {
+            status,
+            content_type: None,
+            body: Vec::new(),
+        }
+    }
+
+    fn json<T: Serialize>(data: &T) -> Self {
+        match serde_json::to_vec(data) {
+            Ok(body) => Self {
+                status: 200,
+                content_type: Some("application/json"),
+                body,
+            },
+            Err(_) => Self::status(500),
+        }
+    }
+}
+
+/// Handler for web service endpoints
+pub struct WebServiceHandler {
+    store: Arc<dyn RegistrationStore>,
+    passes: Box<PassProvider>,
+    tokens: Option<Box<TokenProvider>>,
+    log: Box<LogHandler>,
+    personalize: Option<Box<PersonalizeHandler>>,
+    base_path: String,
+}
+
+impl WebServiceHandler {
+    /// Create handler with registration store and callback, that makes packages
+    pub fn new<F>(store: Arc<dyn RegistrationStore>, passes: F) -> Self
+    where
+        F: Fn(&str, &str) -> Result<Option<Package>, Error> + Send + Sync + 'static,
+    {
+        Self {
+            store,
+            passes: Box::new(passes),
+            tokens: None,
+            log: Box::new(|_| {}),
+            personalize: None,
+            base_path: String::new(),
+        }
+    }
+
+    /// Set path of web service URL (e.g. `/passes` for `https://example.com/passes`)
+    pub fn base_path(mut self, path: &str) -> Self {
+        self.base_path = String::from(path.trim_end_matches('/'));
+        self
+    }
+
+    /// Set callback, that returns [authentication token](crate::web_service::WebService::authentication_token) of pass.
+    ///
+    /// Token is checked before making package, register and unregister requests don't make package at all.
+    /// By default token is read from package, made by [PassProvider].
+    pub fn on_token<F>(mut self, tokens: F) -> Self
+    where
+        F: Fn(&str, &str) -> Result<Option<String>, Error> + Send + Sync + 'static,
+    {
+        self.tokens = Some(Box::new(tokens));
+        self
+    }
+
+    /// Set callback for log messages from devices. By default messages are ignored.
+    pub fn on_log<F>(mut self, log: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.log = Box::new(log);
+        self
+    }
+
+    /// Set callback for personalize requests, enables personalize endpoint.
+    ///
+    /// Callback saves user info (e.g. signs user up for rewards program), then handler responds
+    /// with personalization token, signed by [signer](Package::signer) of pass package.
+    /// Personalized pass is delivered by push notification, as usual pass update.
+    pub fn on_personalize<F>(mut self, personalize: F) -> Self
+    where
+        F: Fn(&str, &str, &PersonalizationRequest) -> Result<(), Error> + Send + Sync + 'static,
+    {
+        self.personalize = Some(Box::new(personalize));
+        self
+    }
+
+    /// Registration store of handler
+    pub fn store(&self) -> &Arc<dyn RegistrationStore> {
+        &self.store
+    }
+
+    /// Handle requests from server, until it's [unblocked](tiny_http::Server::unblock)
+    pub fn serve(&self, server: &tiny_http::Server) {
+        for mut request in server.incoming_requests() {
+            let mut body = Vec::new();
+            let response = match request.as_reader().read_to_end(&mut body) {
+                Ok(_) => self.handle(&Request {
+                    method: request.method().as_str().to_uppercase(),
+                    url: String::from(request.url()),
+                    authorization: request
+                        .headers()
+                        .iter()
+                        .find(|header| header.field.equiv("Authorization"))
+                        .map(|header| String::from(header.value.as_str())),
+                    body,
+                }),
+                Err(_) => Response::status(400),
+            };
+
+            let mut http_response =
+                tiny_http::Response::from_data(response.body).with_status_code(response.status);
+            if let Some(content_type) = response.content_type {
+                if let Ok(header) = tiny_http::Header::from_bytes("Content-Type", content_type) {
+                    http_response = http_response.with_header(header);
+                }
+            }
+            // Client can close connection before response, nothing to do
+            let _ = request.respond(http_response);
+        }
+    }
+
+    /// Handle single request
+    pub fn handle(&self, request: &Request) -> Response {
+        let (path, query) = request
+            .url
+            .split_once('?')
+            .unwrap_or((request.url.as_str(), ""));
+        let Some(path) = path
+            .strip_prefix(&self.base_path)
+            .and_then(|path| path.strip_prefix("/v1/"))
+        else {
+            return Response::status(404);
+        };
+        let segments: Vec<String> = path.split('/').map(percent_decode).collect();
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        let result = match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["devices", device, "registrations", pass_type, serial]) => {
+                self.register(request, device, pass_type, serial)
+            }
+            ("DELETE", ["devices", device, "registrations", pass_type, serial]) => {
+                self.unregister(request, device, pass_type, serial)
+            }
+            ("GET", ["devices", device, "registrations", pass_type]) => {
+                self.serial_numbers(device, pass_type, query)
+            }
+            ("GET", ["passes", pass_type, serial]) => self.latest_pass(request, pass_type, serial),
+            ("POST", ["passes", pass_type, serial, "personalize"]) => {
+                self.personalize(request, pass_type, serial)
+            }
+            ("POST", ["log"]) => Ok(self.log(request)),
+            (_, ["devices", _, "registrations", _, _])
+            | (_, ["devices", _, "registrations", _])
+            | (_, ["passes", _, _])
+            | (_, ["passes", _, _, "personalize"])
+            | (_, ["log"]) => Ok(Response::status(405)),
+            _ => Ok(Response::status(404)),
+        };
+
+        result.unwrap_or_else(|_| Response::status(500))
+    }
+
+    fn register(
+        &self,
+        request: &Request,
+        device: &str,
+        pass_type: &str,
+        serial: &str,
+    ) -> Result<Response, Error> {
+        if !self.is_authorized(request, pass_type, serial)? {
+            return Ok(Response::status(401));
+        }
+        let Ok(registration) = serde_json::from_slice::<RegistrationRequest>(&request.body) else {
+            return Ok(Response::status(400));
+        };
+
+        let created = self
+            .store
+            .register(device, &registration.push_token, pass_type, serial)?;
+        Ok(Response::status(if created { 201 } else { 200 }))
+    }
+
+    fn unregister(
+        &self,
+        request: &Request,
+        device: &str,
+        pass_type: &str,
+        serial: &str,
+    ) -> Result<Response, Error> {
+        if !self.is_authorized(request, pass_type, serial)? {
+            return Ok(Response::status(401));
+        }
+        self.store.unregister(device, pass_type, serial)?;
+        Ok(Response::status(200))
+    }
+
+    fn serial_numbers(
+        &self,
+        device: &str,
+        pass_type: &str,
+        query: &str,
+    ) -> Result<Response, Error> {
+        let since = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(name, _)| *name == "passesUpdatedSince")
+            .map(|(_, value)| value.parse::<u64>());
+        let since = match since {
+            Some(Ok(tag)) => Some(tag),
+            Some(Err(_)) => return Ok(Response::status(400)),
+            None => None,
+        };
+
+        match self.store.updated_serials(device, pass_type, since)? {
+            Some(updated) => Ok(Response::json(&SerialNumbersResponse {
+                serial_numbers: updated.serial_numbers,
+                last_updated: updated.last_updated.to_string(),
+            })),
+            None => Ok(Response::status(204)),
+        }
+    }
+
+    fn latest_pass(
+        &self,
+        request: &Request,
+        pass_type: &str,
+        serial: &str,
+    ) -> Result<Response, Error> {
+        let Some(mut package) = self.authorize(request, pass_type, serial)? else {
+            return Ok(Response::status(401));
+        };
+
+        let mut data = Cursor::new(Vec::new());
+        package.write(&mut data)?;
+        Ok(Response {
+            status: 200,
+            content_type: Some("application/vnd.apple.pkpass"),
+            body: data.into_inner(),
+        })
+    }
+
+    fn personalize(
+        &self,
+        request: &Request,
+        pass_type: &str,
+        serial: &str,
+    ) -> Result<Response, Error> {
+        let Some(personalize) = &self.personalize else {
+            return Ok(Response::status(404));
+        };
+        let Ok(personalization) = serde_json::from_slice::<PersonalizationRequest>(&request.body)
+        else {
+            return Ok(Response::status(400));
+        };
+        let Some(package) = (self.passes)(pass_type, serial)? else {
+            return Ok(Response::status(404));
+        };
+        let Some(signer) = package.signer.as_deref() else {
+            return Ok(Response::status(500));
+        };
+
+        personalize(pass_type, serial, &personalization)?;
+        Ok(Response {
+            status: 200,
+            content_type: Some("application/octet-stream"),
+            body: personalization.sign_token(signer)?,
+        })
+    }
+
+    fn log(&self, request: &Request) -> Response {
+        let Ok(log) = serde_json::from_slice::<LogRequest>(&request.body) else {
+            return Response::status(400);
+        };
+        for message in &log.logs {
+            (self.log)(message);
+        }
+        Response::status(200)
+    }
+
+    /// Check `ApplePass <token>` authorization, returns package of authorized pass
+    fn authorize(
+        &self,
+        request: &Request,
+        pass_type: &str,
+        serial: &str,
+    ) -> Result<Option<Package>, Error> {
+        if self.tokens.is_some() && !self.is_authorized(request, pass_type, serial)? {
+            return Ok(None);
+        }
+        let Some(token) = request_token(request) else {
+            return Ok(None);
+        };
+
+        let Some(package) = (self.passes)(pass_type, serial)? else {
+            return Ok(None);
+        };
+        let authorized = package
+            .pass
+            .web_service
+            .as_ref()
+            .is_some_and(|web_service| token_matches(&web_service.authentication_token, token));
+        Ok(authorized.then_some(package))
+    }
+
+    /// Check `ApplePass <token>` authorization, package is made only without [token callback](WebServiceHandler::on_token)
+    fn is_authorized(
+        &self,
+        request: &Request,
+        pass_type: &str,
+        serial: &str,
+    ) -> Result<bool, Error> {
+        let Some(tokens) = &self.tokens else {
+            return Ok(self.authorize(request, pass_type, serial)?.is_some());
+        };
+        let Some(token) = request_token(request) else {
+            return Ok(false);
+        };
+        Ok(tokens(pass_type, serial)?.is_some_and(|expected| token_matches(&expected, token)))
+    }
+}
+
+/// Token of `ApplePass <token>` authorization header
+fn request_token(request: &Request) -> Option<&str> {
+    request
+        .authorization
+        .as_deref()
+        .and_then(|value| value.strip_prefix("ApplePass "))
+}
+
+/// Compare tokens in constant time
+fn token_matches(expected: &str, token: &str) -> bool {
+    expected.len() == token.len() && openssl::memcmp::eq(expected.as_bytes(), token.as_bytes())
+}
+
+/// Decode `%XX` escapes in URL path segment
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        sync::{Arc, Mutex},
+    };
+
+    use crate::{
+        sign::{self, TestSigner},
+        store::MemoryStore,
+        web_service::WebService,
+        PassBuilder, PassConfig,
+    };
+
+    use super::*;
+
+    const REGISTRATION_URL: &str =
+        "/passes/v1/devices/device1/registrations/com.example.pass/ABC%20123";
+
+    fn make_handler() -> WebServiceHandler {
+        WebServiceHandler::new(Arc::new(MemoryStore::new()), |pass_type, serial| {
+            if pass_type != "com.example.pass" || serial != "ABC 123" {
+                return Ok(None);
+            }
+            let pass = PassBuilder::new(PassConfig {
+                organization_name: String::from("Apple inc."),
+                description: String::from("Example pass"),
+                pass_type_identifier: String::from(pass_type),
+                team_identifier: String::from("AA00AA0A0A"),
+                serial_number: String::from(serial),
+            })
+            .web_service(WebService {
+                authentication_token: String::from("secret-token-0123456789"),
+                web_service_url: String::from("https://example.com/passes"),
+            })
+            .build();
+            Ok(Some(Package::new(pass)))
+        })
+        .base_path("/passes/")
+    }
+
+    fn request(method: &str, url: &str, token: Option<&str>, body: &str) -> Request {
+        Request {
+            method: String::from(method),
+            url: String::from(url),
+            authorization: token.map(|token| format!("ApplePass {}", token)),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn register_device() {
+        let handler = make_handler();
+        let body = r#"{"pushToken": "push1"}"#;
+
+        let response = handler.handle(&request("POST", REGISTRATION_URL, None, body));
+        assert_eq!(401, response.status);
+        let response = handler.handle(&request("POST", REGISTRATION_URL, Some("wrong"), body));
+        assert_eq!(401, response.status);
+
+        let token = Some("secret-token-0123456789");
+        let response = handler.handle(&request("POST", REGISTRATION_URL, token, body));
+        assert_eq!(201, response.status);
+        let response = handler.handle(&request("POST", REGISTRATION_URL, token, body));
+        assert_eq!(200, response.status);
+        let response = handler.handle(&request("POST", REGISTRATION_URL, token, "{}"));
+        assert_eq!(400, response.status);
+
+        assert_eq!(
+            vec!["push1"],
+            handler
+                .store()
+                .push_tokens("com.example.pass", "ABC 123")
+                .unwrap()
+        );
+
+        let response = handler.handle(&request("DELETE", REGISTRATION_URL, token, ""));
+        assert_eq!(200, response.status);
+        assert!(handler
+            .store()
+            .push_tokens("com.example.pass", "ABC 123")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn register_device_with_token_callback() {
+        let packages = Arc::new(Mutex::new(0));
+        let handler = {
+            let packages = packages.clone();
+            let passes = make_handler().passes;
+            WebServiceHandler::new(Arc::new(MemoryStore::new()), move |pass_type, serial| {
+                *packages.lock().unwrap() += 1;
+                passes(pass_type, serial)
+            })
+            .base_path("/passes")
+            .on_token(|pass_type, serial| {
+                Ok((pass_type == "com.example.pass" && serial == "ABC 123")
+                    .then(|| String::from("secret-token-0123456789")))
+            })
+        };
+        let body = r#"{"pushToken": "push1"}"#;
+        let token = Some("secret-token-0123456789");
+
+        let response = handler.handle(&request("POST", REGISTRATION_URL, Some("wrong"), body));
+        assert_eq!(401, response.status);
+        let response = handler.handle(&request("POST", REGISTRATION_URL, token, body));
+        assert_eq!(201, response.status);
+        let response = handler.handle(&request("DELETE", REGISTRATION_URL, token, ""));
+        assert_eq!(200, response.status);
+        assert_eq!(0, *packages.lock().unwrap());
+
+        // Package is made only for authorized request
+        let url = "/passes/v1/passes/com.example.pass/ABC%20123";
+        let response = handler.handle(&request("GET", url, Some("wrong"), ""));
+        assert_eq!(401, response.status);
+        assert_eq!(0, *packages.lock().unwrap());
+        let response = handler.handle(&request("GET", url, token, ""));
+        assert_eq!(200, response.status);
+        assert_eq!(1, *packages.lock().unwrap());
+    }
+
+    #[test]
+    fn serial_numbers() {
+        let handler = make_handler();
+        let url = "/passes/v1/devices/device1/registrations/com.example.pass";
+
+        let response = handler.handle(&request("GET", url, None, ""));
+        assert_eq!(204, response.status);
+
+        handler
+            .store()
+            .register("device1", "push1", "com.example.pass", "ABC 123")
+            .unwrap();
+        let tag = handler
+            .store()
+            .mark_updated("com.example.pass", "ABC 123")
+            .unwrap();
+
+        let response = handler.handle(&request("GET", url, None, ""));
+        assert_eq!(200, response.status);
+        assert_eq!(Some("application/json"), response.content_type);
+        let json: SerialNumbersResponse = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(vec!["ABC 123"], json.serial_numbers);
+        assert_eq!(tag.to_string(), json.last_updated);
+
+        let url_since = format!("{}?passesUpdatedSince={}", url, json.last_updated);
+        let response = handler.handle(&request("GET", &url_since, None, ""));
+        assert_eq!(204, response.status);
+
+        let response = handler.handle(&request(
+            "GET",
+            &format!("{}?passesUpdatedSince=x", url),
+            None,
+            "",
+        ));
+        assert_eq!(400, response.status);
+    }
+
+    #[test]
+    fn log_and_unknown_endpoints() {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let log = messages.clone();
+        let handler =
+            make_handler().on_log(move |message| log.lock().unwrap().push(String::from(message)));
+
+        let body = r#"{"logs": ["first", "second"]}"#;
+        let response = handler.handle(&request("POST", "/passes/v1/log", None, body));
+        assert_eq!(200, response.status);
+        assert_eq!(vec!["first", "second"], *messages.lock().unwrap());
+
+        let response = handler.handle(&request("GET", "/passes/v1/log", None, ""));
+        assert_eq!(405, response.status);
+        let response = handler.handle(&request("GET", "/passes/v2/log", None, ""));
+        assert_eq!(404, response.status);
+        let response = handler.handle(&request("GET", "/other/v1/log", None, ""));
+        assert_eq!(404, response.status);
+    }
+
+    #[test]
+    fn personalize_pass() {
+        let url = "/v1/passes/com.example.pass/ABC%20123/personalize";
+        let body = r#"{
+            "personalizationToken": "token-0123",
+            "requiredPersonalizationInfo": { "fullName": "John Appleseed" }
+        }"#;
+
+        // Endpoint is disabled without callback
+        let response =
+            make_handler().handle(&request("POST", &format!("/passes{}", url), None, body));
+        assert_eq!(404, response.status);
+
+        let signer = Arc::new(TestSigner::new().unwrap());
+        let names = Arc::new(Mutex::new(Vec::new()));
+        let handler = {
+            let signer = signer.clone();
+            let names = names.clone();
+            WebServiceHandler::new(Arc::new(MemoryStore::new()), move |pass_type, serial| {
+                let pass = PassBuilder::new(PassConfig {
+                    organization_name: String::from("Apple inc."),
+                    description: String::from("Example pass"),
+                    pass_type_identifier: String::from(pass_type),
+                    team_identifier: String::from("AA00AA0A0A"),
+                    serial_number: String::from(serial),
+                })
+                .build();
+                let mut package = Package::new(pass);
+                package.add_signer(Box::new(signer.clone()));
+                Ok(Some(package))
+            })
+            .on_personalize(move |_, serial, personalization| {
+                let info = &personalization.required_personalization_info;
+                names
+                    .lock()
+                    .unwrap()
+                    .push((String::from(serial), info.full_name.clone()));
+                Ok(())
+            })
+        };
+
+        let response = handler.handle(&request("POST", url, None, body));
+        assert_eq!(200, response.status);
+        assert_eq!(Some("application/octet-stream"), response.content_type);
+        sign::verify(
+            sign::WWDR::Custom(signer.wwdr_pem()),
+            b"token-0123",
+            &response.body,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![(
+                String::from("ABC 123"),
+                Some(String::from("John Appleseed"))
+            )],
+            *names.lock().unwrap()
+        );
+
+        let response = handler.handle(&request("POST", url, None, "{}"));
+        assert_eq!(400, response.status);
+        let response = handler.handle(&request("GET", url, None, ""));
+        assert_eq!(405, response.status);
+    }
+
+    #[test]
+    fn serve_latest_pass() {
+        let handler = Arc::new(make_handler());
+        let server = Arc::new(tiny_http::Server::http("127.0.0.1:0").unwrap());
+        let addr = server.server_addr().to_ip().unwrap();
+
+        let thread = {
+            let handler = handler.clone();
+            let server = server.clone();
+            std::thread::spawn(move || handler.serve(&server))
+        };
+
+        let get = |token: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(
+                stream,
+                "GET /passes/v1/passes/com.example.pass/ABC%20123 HTTP/1.1\r\n\
+                 Host: localhost\r\n\
+                 Authorization: ApplePass {}\r\n\
+                 Connection: close\r\n\r\n",
+                token
+            )
+            .unwrap();
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response).unwrap();
+            response
+        };
+
+        let response = get("secret-token-0123456789");
+        let text = String::from_utf8_lossy(&response);
+        assert!(text.starts_with("HTTP/1.1 200"));
+        assert!(text.contains("Content-Type: application/vnd.apple.pkpass"));
+
+        // Response body is readable package
+        let body_start = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap() + 4;
+        let package = Package::read(Cursor::new(&response[body_start..])).unwrap();
+        assert_eq!("ABC 123", package.pass.config.serial_number);
+
+        let response = get("wrong-token");
+        assert!(String::from_utf8_lossy(&response).starts_with("HTTP/1.1 401"));
+
+        server.unblock();
+        thread.join().unwrap();
+    }
+}
@@ -0,0 +1,306 @@
+//! Storage of device registrations for pass updates.
+//!
+//! Devices register for updates of a pass through [web service](crate::web_service::WebService).
+//! Store keeps registrations and a tag of the last update for every pass, so devices can ask which passes changed.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use crate::Error;
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// Serial numbers of passes, updated since the tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdatedSerials {
+    /// Serial numbers of updated passes
+    pub serial_numbers: Vec<String>,
+
+    /// Tag of the latest update, device sends it in next request
+    pub last_updated: u64,
+}
+
+/// Storage for device registrations and pass update tags.
+///
+/// Tags are increasing numbers, assigned by [mark_updated](RegistrationStore::mark_updated).
+/// Implementations must be safe for concurrent use.
+pub trait RegistrationStore: Send + Sync {
+    /// Register device for updates of the pass, or update push token of existing registration.
+    ///
+    /// Returns `true` if registration is new.
+    fn register(
+        &self,
+        device_library_identifier: &str,
+        push_token: &str,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<bool, Error>;
+
+    /// Remove registration of device for the pass.
+    ///
+    /// Returns `true` if registration existed. Device is removed with its last registration.
+    fn unregister(
+        &self,
+        device_library_identifier: &str,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<bool, Error>;
+
+    /// Serial numbers of passes registered on device, that are updated after `since` tag.
+    ///
+    /// Without tag returns all passes registered on device. Returns `None` if there are no such passes.
+    fn updated_serials(
+        &self,
+        device_library_identifier: &str,
+        pass_type_identifier: &str,
+        since: Option<u64>,
+    ) -> Result<Option<UpdatedSerials>, Error>;
+
+    /// Mark pass as updated, returns new tag of the pass
+    fn mark_updated(&self, pass_type_identifier: &str, serial_number: &str) -> Result<u64, Error>;
+
+    /// Push tokens of devices registered for the pass
+    fn push_tokens(
+        &self,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<Vec<String>, Error>;
+}
+
+/// In-memory [RegistrationStore], data is lost on drop.
+///
+/// Useful for tests and single-process services. For persistent storage see `sqlite::SqliteStore` (`sqlite` feature).
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    /// Push token by device library identifier
+    devices: BTreeMap<String, String>,
+
+    /// Registered devices by pass (pass type identifier, serial number)
+    registrations: BTreeMap<(String, String), BTreeSet<String>>,
+
+    /// Last update tag by pass (pass type identifier, serial number)
+    tags: BTreeMap<(String, String), u64>,
+
+    /// Last assigned tag
+    last_tag: u64,
+}
+
+impl MemoryStore {
+    /// Create empty store
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MemoryState> {
+        // State is always consistent between operations, so poisoning can be ignored
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl RegistrationStore for MemoryStore {
+    fn register(
+        &self,
+        device_library_identifier: &str,
+        push_token: &str,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<bool, Error> {
+        let mut state = self.state();
+        state.devices.insert(
+            String::from(device_library_identifier),
+            String::from(push_token),
+        );
+        let created = state
+            .registrations
+            .entry(pass_key(pass_type_identifier, serial_number))
+            .or_default()
+            .insert(String::from(device_library_identifier));
+        Ok(created)
+    }
+
+    fn unregister(
+        &self,
+        device_library_identifier: &str,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<bool, Error> {
+        let mut state = self.state();
+        let key = pass_key(pass_type_identifier, serial_number);
+        let Some(devices) = state.registrations.get_mut(&key) else {
+            return Ok(false);
+        };
+        let removed = devices.remove(device_library_identifier);
+        if devices.is_empty() {
+            state.registrations.remove(&key);
+        }
+
+        let registered = state
+            .registrations
+            .values()
+            .any(|devices| devices.contains(device_library_identifier));
+        if !registered {
+            state.devices.remove(device_library_identifier);
+        }
+        Ok(removed)
+    }
+
+    fn updated_serials(
+        &self,
+        device_library_identifier: &str,
+        pass_type_identifier: &str,
+        since: Option<u64>,
+    ) -> Result<Option<UpdatedSerials>, Error> {
+        let state = self.state();
+        let mut serial_numbers = Vec::new();
+        let mut last_updated = 0;
+        for ((pass_type, serial_number), devices) in &state.registrations {
+            if pass_type != pass_type_identifier || !devices.contains(device_library_identifier) {
+                continue;
+            }
+            let tag = state
+                .tags
+                .get(&pass_key(pass_type, serial_number))
+                .copied()
+                .unwrap_or(0);
+            if since.is_none_or(|since| tag > since) {
+                serial_numbers.push(serial_number.clone());
+                last_updated = last_updated.max(tag);
+            }
+        }
+
+        if serial_numbers.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(UpdatedSerials {
+            serial_numbers,
+            last_updated,
+        }))
+    }
+
+    fn mark_updated(&self, pass_type_identifier: &str, serial_number: &str) -> Result<u64, Error> {
+        let mut state = self.state();
+        state.last_tag += 1;
+        let tag = state.last_tag;
+        state
+            .tags
+            .insert(pass_key(pass_type_identifier, serial_number), tag);
+        Ok(tag)
+    }
+
+    fn push_tokens(
+        &self,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<Vec<String>, Error> {
+        let state = self.state();
+        let tokens = state
+            .registrations
+            .get(&pass_key(pass_type_identifier, serial_number))
+            .into_iter()
+            .flatten()
+            .filter_map(|device| state.devices.get(device).cloned())
+            .collect();
+        Ok(tokens)
+    }
+}
+
+fn pass_key(pass_type_identifier: &str, serial_number: &str) -> (String, String) {
+    (
+        String::from(pass_type_identifier),
+        String::from(serial_number),
+    )
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // Shared checks for all store implementations
+    pub(crate) fn check_register_and_unregister(store: &dyn RegistrationStore) {
+        assert!(store
+            .register("device1", "token1", "com.example.pass", "001")
+            .unwrap());
+        assert!(!store
+            .register("device1", "token2", "com.example.pass", "001")
+            .unwrap());
+        assert!(store
+            .register("device2", "token3", "com.example.pass", "001")
+            .unwrap());
+
+        assert_eq!(
+            vec!["token2", "token3"],
+            store.push_tokens("com.example.pass", "001").unwrap()
+        );
+
+        assert!(store
+            .unregister("device1", "com.example.pass", "001")
+            .unwrap());
+        assert!(!store
+            .unregister("device1", "com.example.pass", "001")
+            .unwrap());
+        assert_eq!(
+            vec!["token3"],
+            store.push_tokens("com.example.pass", "001").unwrap()
+        );
+    }
+
+    pub(crate) fn check_updated_serials(store: &dyn RegistrationStore) {
+        store
+            .register("device1", "token1", "com.example.pass", "001")
+            .unwrap();
+        store
+            .register("device1", "token1", "com.example.pass", "002")
+            .unwrap();
+        store
+            .register("device1", "token1", "com.example.other", "003")
+            .unwrap();
+
+        // All registered passes
+        let updated = store
+            .updated_serials("device1", "com.example.pass", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec!["001", "002"], updated.serial_numbers);
+        assert_eq!(0, updated.last_updated);
+
+        let tag = store.mark_updated("com.example.pass", "002").unwrap();
+        store.mark_updated("com.example.other", "003").unwrap();
+
+        let updated = store
+            .updated_serials("device1", "com.example.pass", Some(0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec!["002"], updated.serial_numbers);
+        assert_eq!(tag, updated.last_updated);
+
+        assert_eq!(
+            None,
+            store
+                .updated_serials("device1", "com.example.pass", Some(tag))
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            store
+                .updated_serials("device2", "com.example.pass", None)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn register_and_unregister() {
+        check_register_and_unregister(&MemoryStore::new());
+    }
+
+    #[test]
+    fn updated_serials() {
+        check_updated_serials(&MemoryStore::new());
+    }
+}
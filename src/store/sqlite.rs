@@ -0,0 +1,297 @@
+use std::{
+    path::Path,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use rusqlite::{params, Connection, TransactionBehavior};
+
+use crate::Error;
+
+use super::{RegistrationStore, UpdatedSerials};
+
+/// Tables of store, created on open
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS devices (
+    device_library_identifier TEXT PRIMARY KEY,
+    push_token TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS registrations (
+    device_library_identifier TEXT NOT NULL REFERENCES devices ON DELETE CASCADE,
+    pass_type_identifier TEXT NOT NULL,
+    serial_number TEXT NOT NULL,
+    PRIMARY KEY (device_library_identifier, pass_type_identifier, serial_number)
+);
+CREATE INDEX IF NOT EXISTS registrations_pass
+    ON registrations (pass_type_identifier, serial_number);
+CREATE TABLE IF NOT EXISTS passes (
+    pass_type_identifier TEXT NOT NULL,
+    serial_number TEXT NOT NULL,
+    tag INTEGER NOT NULL,
+    PRIMARY KEY (pass_type_identifier, serial_number)
+);
+";
+
+/// [RegistrationStore] in SQLite database file.
+///
+/// Requires `sqlite` feature. Every write is a single transaction, so several processes can share one database file.
+///
+/// ```
+/// use passes::store::{sqlite::SqliteStore, RegistrationStore};
+///
+/// let store = SqliteStore::open_in_memory().unwrap();
+/// store
+///     .register("device1", "push-token", "com.example.pass", "ABC123")
+///     .unwrap();
+/// store.mark_updated("com.example.pass", "ABC123").unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SqliteStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open (or create) database file
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::init(Connection::open(path).map_err(store_error)?)
+    }
+
+    /// Open temporary database in memory
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::init(Connection::open_in_memory().map_err(store_error)?)
+    }
+
+    fn init(connection: Connection) -> Result<Self, Error> {
+        // Wait for writers from other processes instead of failing
+        connection
+            .busy_timeout(Duration::from_secs(5))
+            .map_err(store_error)?;
+        connection
+            .execute_batch("PRAGMA foreign_keys = ON;")
+            .map_err(store_error)?;
+        connection.execute_batch(SCHEMA).map_err(store_error)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        // Unfinished transactions are rolled back on drop, so poisoning can be ignored
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl RegistrationStore for SqliteStore {
+    fn register(
+        &self,
+        device_library_identifier: &str,
+        push_token: &str,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<bool, Error> {
+        let mut connection = self.connection();
+        let transaction = connection
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(store_error)?;
+        transaction
+            .execute(
+                "INSERT INTO devices (device_library_identifier, push_token) VALUES (?1, ?2)
+                 ON CONFLICT (device_library_identifier) DO UPDATE SET push_token = excluded.push_token",
+                params![device_library_identifier, push_token],
+            )
+            .map_err(store_error)?;
+        let inserted = transaction
+            .execute(
+                "INSERT OR IGNORE INTO registrations
+                 (device_library_identifier, pass_type_identifier, serial_number) VALUES (?1, ?2, ?3)",
+                params![device_library_identifier, pass_type_identifier, serial_number],
+            )
+            .map_err(store_error)?;
+        transaction.commit().map_err(store_error)?;
+        Ok(inserted > 0)
+    }
+
+    fn unregister(
+        &self,
+        device_library_identifier: &str,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<bool, Error> {
+        let mut connection = self.connection();
+        let transaction = connection
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(store_error)?;
+        let deleted = transaction
+            .execute(
+                "DELETE FROM registrations WHERE device_library_identifier = ?1
+                 AND pass_type_identifier = ?2 AND serial_number = ?3",
+                params![
+                    device_library_identifier,
+                    pass_type_identifier,
+                    serial_number
+                ],
+            )
+            .map_err(store_error)?;
+        transaction
+            .execute(
+                "DELETE FROM devices WHERE device_library_identifier = ?1 AND NOT EXISTS
+                 (SELECT 1 FROM registrations WHERE device_library_identifier = ?1)",
+                params![device_library_identifier],
+            )
+            .map_err(store_error)?;
+        transaction.commit().map_err(store_error)?;
+        Ok(deleted > 0)
+    }
+
+    fn updated_serials(
+        &self,
+        device_library_identifier: &str,
+        pass_type_identifier: &str,
+        since: Option<u64>,
+    ) -> Result<Option<UpdatedSerials>, Error> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare(
+                "SELECT r.serial_number, COALESCE(p.tag, 0) AS tag FROM registrations r
+                 LEFT JOIN passes p ON p.pass_type_identifier = r.pass_type_identifier
+                 AND p.serial_number = r.serial_number
+                 WHERE r.device_library_identifier = ?1 AND r.pass_type_identifier = ?2
+                 AND (?3 IS NULL OR COALESCE(p.tag, 0) > ?3)
+                 ORDER BY r.serial_number",
+            )
+            .map_err(store_error)?;
+        let since = since.map(tag_to_sql).transpose()?;
+        let rows = statement
+            .query_map(
+                params![device_library_identifier, pass_type_identifier, since],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+            )
+            .map_err(store_error)?;
+
+        let mut serial_numbers = Vec::new();
+        let mut last_updated = 0;
+        for row in rows {
+            let (serial_number, tag) = row.map_err(store_error)?;
+            serial_numbers.push(serial_number);
+            last_updated = last_updated.max(tag as u64);
+        }
+
+        if serial_numbers.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(UpdatedSerials {
+            serial_numbers,
+            last_updated,
+        }))
+    }
+
+    fn mark_updated(&self, pass_type_identifier: &str, serial_number: &str) -> Result<u64, Error> {
+        let mut connection = self.connection();
+        let transaction = connection
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(store_error)?;
+        let tag: i64 = transaction
+            .query_row(
+                "INSERT INTO passes (pass_type_identifier, serial_number, tag)
+                 VALUES (?1, ?2, (SELECT COALESCE(MAX(tag), 0) + 1 FROM passes))
+                 ON CONFLICT (pass_type_identifier, serial_number) DO UPDATE SET tag = excluded.tag
+                 RETURNING tag",
+                params![pass_type_identifier, serial_number],
+                |row| row.get(0),
+            )
+            .map_err(store_error)?;
+        transaction.commit().map_err(store_error)?;
+        Ok(tag as u64)
+    }
+
+    fn push_tokens(
+        &self,
+        pass_type_identifier: &str,
+        serial_number: &str,
+    ) -> Result<Vec<String>, Error> {
+        let connection = self.connection();
+        let mut statement = connection
+            .prepare(
+                "SELECT d.push_token FROM registrations r
+                 JOIN devices d ON d.device_library_identifier = r.device_library_identifier
+                 WHERE r.pass_type_identifier = ?1 AND r.serial_number = ?2
+                 ORDER BY r.device_library_identifier",
+            )
+            .map_err(store_error)?;
+        let tokens = statement
+            .query_map(params![pass_type_identifier, serial_number], |row| {
+                row.get(0)
+            })
+            .map_err(store_error)?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(store_error)?;
+        Ok(tokens)
+    }
+}
+
+/// Tags are stored as SQLite integers
+fn tag_to_sql(tag: u64) -> Result<i64, Error> {
+    i64::try_from(tag).map_err(|source| Error::Store {
+        source: Box::new(source),
+    })
+}
+
+fn store_error(source: rusqlite::Error) -> Error {
+    Error::Store {
+        source: Box::new(source),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::store::tests::{check_register_and_unregister, check_updated_serials};
+
+    use super::*;
+
+    #[test]
+    fn register_and_unregister() {
+        check_register_and_unregister(&SqliteStore::open_in_memory().unwrap());
+    }
+
+    #[test]
+    fn updated_serials() {
+        check_updated_serials(&SqliteStore::open_in_memory().unwrap());
+    }
+
+    #[test]
+    fn persist_in_file() {
+        let path = std::env::temp_dir().join(format!("passes-store-{}.sqlite", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        {
+            let store = SqliteStore::open(&path).unwrap();
+            store
+                .register("device1", "token1", "com.example.pass", "001")
+                .unwrap();
+            store.mark_updated("com.example.pass", "001").unwrap();
+        }
+
+        // Second connection to the same file, like another process
+        let store = SqliteStore::open(&path).unwrap();
+        let other = SqliteStore::open(&path).unwrap();
+        assert_eq!(
+            vec!["token1"],
+            other.push_tokens("com.example.pass", "001").unwrap()
+        );
+        assert_eq!(2, store.mark_updated("com.example.pass", "002").unwrap());
+        assert_eq!(3, other.mark_updated("com.example.pass", "001").unwrap());
+
+        let updated = store
+            .updated_serials("device1", "com.example.pass", Some(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec!["001"], updated.serial_numbers);
+        assert_eq!(3, updated.last_updated);
+
+        drop(store);
+        drop(other);
+        std::fs::remove_file(&path).unwrap();
+    }
+}